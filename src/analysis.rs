@@ -0,0 +1,148 @@
+use crate::error::{Error, ErrorKind};
+use crate::floral::{
+    FloralPart, FloralPartNumber, Formula, Merosity, StamenPosition, Sterile, Whorl,
+};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The number of stamens relative to the perianth, as
+/// used by Judd et al.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Androecium {
+    /// One whorl of stamens, alternating with the petals
+    Haplostemonous,
+    /// One whorl of stamens, opposite the petals
+    Obhaplostemonous,
+    /// Two whorls of stamens, the outer alternating
+    /// with the petals
+    Diplostemonous,
+    /// Two whorls of stamens, the outer opposite
+    /// the petals
+    Obdiplostemonous,
+    /// More stamens than two whorls
+    Polyandrous,
+    /// Fewer stamens than the perianth merosity
+    Oligandrous,
+    /// The stamens can't be compared to the perianth
+    Indeterminate,
+}
+
+impl Display for Androecium {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Androecium::Haplostemonous => write!(f, "haplostemonous"),
+            Androecium::Obhaplostemonous => write!(f, "obhaplostemonous"),
+            Androecium::Diplostemonous => write!(f, "diplostemonous"),
+            Androecium::Obdiplostemonous => write!(f, "obdiplostemonous"),
+            Androecium::Polyandrous => write!(f, "polyandrous"),
+            Androecium::Oligandrous => write!(f, "oligandrous"),
+            Androecium::Indeterminate => write!(f, "indeterminate"),
+        }
+    }
+}
+
+impl FromStr for Androecium {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "haplostemonous" => Ok(Self::Haplostemonous),
+            "obhaplostemonous" => Ok(Self::Obhaplostemonous),
+            "diplostemonous" => Ok(Self::Diplostemonous),
+            "obdiplostemonous" => Ok(Self::Obdiplostemonous),
+            "polyandrous" => Ok(Self::Polyandrous),
+            "oligandrous" => Ok(Self::Oligandrous),
+            "indeterminate" => Ok(Self::Indeterminate),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "androecium: {}, not recognised",
+                other
+            )))),
+        }
+    }
+}
+
+// the total number of parts over all the whorls, as a range
+pub(crate) fn count_parts(part: &FloralPart) -> Option<(FloralPartNumber, FloralPartNumber)> {
+    count_whorls(&part.get_whorls())
+}
+
+// the total number of parts over some whorls, as a range
+fn count_whorls(whorls: &[Whorl]) -> Option<(FloralPartNumber, FloralPartNumber)> {
+    let mut min = 0;
+    let mut max = 0;
+    let mut min_infinite = false;
+    let mut infinite = false;
+    for whorl in whorls {
+        let (lo, hi) = match (whorl.get_number(), whorl.get_min(), whorl.get_max()) {
+            (Some(n), _, _) => (n.clone(), n.clone()),
+            (None, Some(lo), Some(hi)) => (lo.clone(), hi.clone()),
+            _ => return None,
+        };
+        match lo {
+            FloralPartNumber::Finite(n) => min += n,
+            FloralPartNumber::Infinite => min_infinite = true,
+            FloralPartNumber::Fractional(_) => return None,
+        }
+        match hi {
+            FloralPartNumber::Finite(n) => max += n,
+            FloralPartNumber::Infinite => infinite = true,
+            FloralPartNumber::Fractional(_) => return None,
+        }
+    }
+    let min = if min_infinite {
+        FloralPartNumber::Infinite
+    } else {
+        FloralPartNumber::Finite(min)
+    };
+    let max = if infinite || min_infinite {
+        FloralPartNumber::Infinite
+    } else {
+        FloralPartNumber::Finite(max)
+    };
+    Some((min, max))
+}
+
+impl Formula {
+    /// Classify the androecium relative to the perianth, from
+    /// the number of stamens and the merosity of the perianth.
+    /// The position of the stamens, if known, tells the
+    /// obhaplostemonous and obdiplostemonous cases apart.
+    /// Sterile whorls (staminodes) aren't counted as stamens.
+    pub fn androecium(&self) -> Androecium {
+        let perianth = [self.get_tepals(), self.get_sepals(), self.get_petals()];
+        // without a perianth, the merosity comes from the stamens themselves
+        let base = match self.merosity() {
+            Merosity::Merous(n) if Merosity::from_parts(&perianth) != Merosity::Indeterminate => n,
+            _ => return Androecium::Indeterminate,
+        };
+        let stamens = match self.get_stamens() {
+            Some(s) => s,
+            None => return Androecium::Indeterminate,
+        };
+        let opposite = stamens.get_stamen_position() == Some(StamenPosition::Opposite);
+        let fertile: Vec<Whorl> = stamens
+            .get_whorls()
+            .into_iter()
+            .filter(|e| matches!(e.get_sterility(), Sterile::Fertile))
+            .collect();
+
+        match count_whorls(&fertile) {
+            Some((FloralPartNumber::Infinite, _)) => Androecium::Polyandrous,
+            Some((FloralPartNumber::Finite(min), _)) if min > 2 * base => Androecium::Polyandrous,
+            Some((FloralPartNumber::Finite(0), _)) => Androecium::Indeterminate,
+            Some((FloralPartNumber::Finite(min), FloralPartNumber::Finite(max))) if min == max => {
+                match min {
+                    n if n < base => Androecium::Oligandrous,
+                    n if n == base && opposite => Androecium::Obhaplostemonous,
+                    n if n == base => Androecium::Haplostemonous,
+                    n if n == 2 * base && opposite => Androecium::Obdiplostemonous,
+                    n if n == 2 * base => Androecium::Diplostemonous,
+                    _ => Androecium::Indeterminate,
+                }
+            }
+            // a range of stamen numbers, all fewer than the merosity
+            Some((_, FloralPartNumber::Finite(max))) if max < base => Androecium::Oligandrous,
+            _ => Androecium::Indeterminate,
+        }
+    }
+}
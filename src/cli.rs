@@ -1,156 +1,994 @@
 use crate::error::{Error, ErrorKind, Result};
 use crate::{
-    explain::ExplainFloralFormula,
-    floral::{FlowerType, Formula},
+    analysis::Androecium,
+    completions::{self, FlagGroup, Shell, COMMANDS, FLAGS},
+    explain::{ExplainFloralFormula, Verbosity},
+    floral::{Flower, FlowerType, Formula, FruitQuery, Inflorescence, Merosity},
+    glossary,
+    i18n::{t, Locale},
+    output::{render_tables, OutputFormat, Table, Value},
+    quiz::{self, QuestionKind, Rng},
+    stats::Stats,
 };
 use std::cmp;
+use std::ffi::OsString;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use textwrap::Options;
 
 const VERSION: f32 = 0.11;
 
-fn generate_help_str() -> String {
-    format!(
+// the help, with the commands and flags as completed in the shells
+pub(crate) fn generate_help_str() -> String {
+    let mut help = format!(
         "\
 floral v{}
 
 USAGE:
-  floral [FLAGS] <TAXON RANK>
-
-FLAGS:
-        
-  GENERAL FLAGS:
-  
-  -h, --help            Prints help information
-  -a, --all             Print all family information
-  -e, --explain         Explain the floral formula
-  -v, --version         Print version information only
-  -o, --order           Search plant orders, not families
-  -d, --data            Return data for plant taxon rank
-
-ARGS:
-  <TAXON RANK>          Flowering plant family/order (with -o) name 
+  floral <COMMAND> [FLAGS] [ARGS]
+  floral [FLAGS] <TAXON RANK>...
+
+COMMANDS:
+
 ",
         VERSION
-    )
+    );
+    for command in COMMANDS {
+        let usage = format!("{} {}", command.get_name(), command.get_args());
+        help += &help_entry(usage.trim_end(), command.get_help(), command.get_details());
+    }
+    help += "\nFLAGS:\n";
+    for group in FlagGroup::ALL {
+        help += &format!("\n  {}:\n\n", group);
+        for flag in FLAGS.iter().filter(|e| e.get_group() == group) {
+            let usage = flag.forms().join(", ");
+            help += &help_entry(&usage, flag.get_help(), flag.get_details());
+        }
+        if group == FlagGroup::Filter {
+            help += "\n  Filters imply `list` if no name is given.\n";
+        }
+    }
+    help += "\nARGS:\n";
+    help += &help_entry(
+        "<TAXON RANK>",
+        "Flowering plant family/order (with -o) name,",
+        " shown as with `floral show`",
+    );
+    help
+}
+
+// a command or flag in the help, with its description wrapped in
+// a column of its own, below the usage if that is too long
+fn help_entry(usage: &str, help: &str, details: &str) -> String {
+    let indent = " ".repeat(24);
+    let options = Options::new(70)
+        .initial_indent(&indent)
+        .subsequent_indent(&indent)
+        .wrap_algorithm(textwrap::WrapAlgorithm::FirstFit);
+    let description = format!("{}{}", help, details);
+    let mut lines = textwrap::wrap(&description, options);
+    let mut entry = format!("  {:<22}", usage);
+    if usage.len() < 22 {
+        entry.truncate(24);
+        entry += lines.remove(0).trim_start();
+    }
+    entry += "\n";
+    for line in lines {
+        entry += &format!("{}\n", line);
+    }
+    entry
+}
+
+// the subcommands which work on the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Show,
+    List,
+    Search,
+    Export,
+    Compare,
+    Stats,
+    Quiz,
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Show => write!(f, "show"),
+            Command::List => write!(f, "list"),
+            Command::Search => write!(f, "search"),
+            Command::Export => write!(f, "export"),
+            Command::Compare => write!(f, "compare"),
+            Command::Stats => write!(f, "stats"),
+            Command::Quiz => write!(f, "quiz"),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "show" => Ok(Self::Show),
+            "list" => Ok(Self::List),
+            "search" => Ok(Self::Search),
+            "export" => Ok(Self::Export),
+            "compare" => Ok(Self::Compare),
+            "stats" => Ok(Self::Stats),
+            "quiz" => Ok(Self::Quiz),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "command: {}, not recognised (try show, list, search, export, compare, stats or quiz)",
+                other
+            )))),
+        }
+    }
+}
+
+// the flags shared by all of the subcommands
+pub(crate) struct CliOptions {
+    cli_order: bool,
+    cli_explain: bool,
+    locale: Locale,
+    verbosity: Verbosity,
+    format: Option<OutputFormat>,
+    filters: Filters,
+}
+
+impl CliOptions {
+    pub(crate) fn parse(
+        pargs: &mut pico_args::Arguments,
+        explain_level: Option<Verbosity>,
+    ) -> Result<Self> {
+        Ok(Self {
+            cli_order: pargs.contains(["-o", "--order"]),
+            cli_explain: pargs.contains(["-e", "--explain"]) || explain_level.is_some(),
+            verbosity: explain_level.unwrap_or_default(),
+            locale: pargs
+                .opt_value_from_fn(["-l", "--lang"], Locale::from_str)?
+                .unwrap_or_default(),
+            format: pargs.opt_value_from_fn("--format", OutputFormat::from_str)?,
+            filters: Filters {
+                inflorescence: pargs
+                    .opt_value_from_fn(["-i", "--inflorescence"], Inflorescence::from_str)?,
+                fruit: pargs.opt_value_from_fn(["-f", "--fruit"], FruitQuery::from_str)?,
+                merosity: pargs.opt_value_from_fn(["-m", "--merosity"], Merosity::from_str)?,
+                androecium: pargs.opt_value_from_fn("--androecium", Androecium::from_str)?,
+            },
+        })
+    }
+}
+
+// the flags of the quiz command
+struct QuizOptions {
+    number: usize,
+    seed: Option<u64>,
+    kinds: Vec<QuestionKind>,
+    sheet: Option<String>,
+    key: Option<String>,
+}
+
+impl Default for QuizOptions {
+    fn default() -> Self {
+        Self {
+            number: 10,
+            seed: None,
+            kinds: QuestionKind::ALL.to_vec(),
+            sheet: None,
+            key: None,
+        }
+    }
+}
+
+impl QuizOptions {
+    fn parse(pargs: &mut pico_args::Arguments) -> Result<Self> {
+        let default = Self::default();
+        let kinds = |s: &str| -> Result<Vec<QuestionKind>> {
+            s.split(',').map(QuestionKind::from_str).collect()
+        };
+        Ok(Self {
+            number: pargs
+                .opt_value_from_str(["-n", "--number"])?
+                .unwrap_or(default.number),
+            seed: pargs.opt_value_from_str("--seed")?,
+            kinds: pargs
+                .opt_value_from_fn(["-t", "--type"], kinds)?
+                .unwrap_or(default.kinds),
+            sheet: pargs.opt_value_from_str("--sheet")?,
+            key: pargs.opt_value_from_str("--key")?,
+        })
+    }
 }
 
 /// Parse the command line arguments, and execute the application.
 pub fn parse_args() -> Result<()> {
-    let mut pargs = pico_args::Arguments::from_env();
+    let mut args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    let contents;
+    let source = match take_db(&mut args)? {
+        Some(path) => {
+            contents = std::fs::read_to_string(&path).map_err(|e| {
+                Error::new(ErrorKind::GenericCli(format!(
+                    "couldn't read the database {}: {}",
+                    path.display(),
+                    e
+                )))
+            })?;
+            contents.as_str()
+        }
+        None => crate::parse::DATA,
+    };
+    if args.first().and_then(|e| e.to_str()) == Some("shell") {
+        return crate::shell::run(args[1..].to_vec(), source);
+    }
+    let data = crate::parse::parse_data_from(source)?;
+    run(args, &data)
+}
+
+// `--db` goes with any command, the shell's too, so take it out before
+// the command is looked at
+fn take_db(args: &mut Vec<OsString>) -> Result<Option<PathBuf>> {
+    let mut pargs = pico_args::Arguments::from_vec(std::mem::take(args));
+    let path = pargs.opt_value_from_os_str("--db", |e| Ok::<PathBuf, Error>(PathBuf::from(e)))?;
+    *args = pargs.finish();
+    Ok(path)
+}
+
+// the parsed database, keyed by order, family and flower
+pub(crate) type Data<'a> = std::collections::BTreeMap<(&'a str, &'a str, Flower), Formula>;
+
+// whether a name is a family (false) or an order (true) in the database
+pub(crate) fn name_kind(name: &str, data: &Data) -> Option<bool> {
+    if data
+        .keys()
+        .any(|(_, family, _)| family.eq_ignore_ascii_case(name))
+    {
+        Some(false)
+    } else if data
+        .keys()
+        .any(|(order, _, _)| order.eq_ignore_ascii_case(name))
+    {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Execute a command, given the arguments without the program name, on
+/// a database which has already been parsed. The shell runs each line
+/// it reads through here.
+pub(crate) fn run(mut args: Vec<OsString>, data: &Data) -> Result<()> {
+    let explain_level = take_explain_level(&mut args)?;
+    let first = args.first().and_then(|e| e.to_str()).map(|e| e.to_string());
+    match first.as_deref() {
+        Some("glossary") => {
+            args.remove(0);
+            return print_glossary(args);
+        }
+        Some("explain") => {
+            args.remove(0);
+            return explain(args, explain_level.unwrap_or_default(), data);
+        }
+        Some("completions") => {
+            args.remove(0);
+            return print_completions(args, data);
+        }
+        _ => (),
+    }
+    let command = first.and_then(|e| Command::from_str(&e).ok());
+    if command.is_some() {
+        args.remove(0);
+    }
+    let mut pargs = pico_args::Arguments::from_vec(args);
 
     if pargs.contains(["-h", "--help"]) {
         print!("{}", generate_help_str());
-        std::process::exit(0);
+        return Ok(());
     }
 
     if pargs.contains(["-v", "--version"]) {
-        print!("floral v{}", VERSION);
-        std::process::exit(0);
+        println!("floral v{}", VERSION);
+        return Ok(());
     }
 
     let cli_all = pargs.contains(["-a", "--all"]);
-    let cli_explain = pargs.contains(["-e", "--explain"]);
-    let cli_order = pargs.contains(["-o", "--order"]);
     let print_data = pargs.contains(["-d", "--data"]);
+    let options = CliOptions::parse(&mut pargs, explain_level)?;
+    let quiz_options = match command {
+        Some(Command::Quiz) => QuizOptions::parse(&mut pargs)?,
+        _ => QuizOptions::default(),
+    };
+    let names: Vec<String> = pargs
+        .finish()
+        .into_iter()
+        .map(|e| e.to_string_lossy().to_string())
+        .collect();
+
+    // the old flags stand in for the commands
+    let command = match command {
+        Some(c) => c,
+        None if print_data => Command::Export,
+        None if names.is_empty() && (cli_all || options.filters.is_some()) => Command::List,
+        None if names.is_empty() => {
+            print!("{}", generate_help_str());
+            return Ok(());
+        }
+        // names with -a are shown in the full list, each entry once
+        None => Command::Show,
+    };
 
-    let data = crate::parse::parse_data()?;
-    let data_keys: Vec<_> = if cli_order {
+    match command {
+        Command::Show => {
+            if names.is_empty() && !cli_all {
+                return Err(Error::new(ErrorKind::GenericCli(
+                    "give a family (or order, with -o) to show, e.g. floral show liliaceae".into(),
+                )));
+            }
+            let selection = select(data, &names, cli_all, &options)?;
+            print_formulae(&selection, &options);
+        }
+        Command::List => match names.first().map(|e| e.as_str()) {
+            None => {
+                let selection = select(data, &[], true, &options)?;
+                print_formulae(&selection, &options);
+            }
+            Some(topic @ ("orders" | "families")) => {
+                // any names after the topic narrow it down, e.g. to an order with -o
+                let selection = select(data, &names[1..], names.len() == 1, &options)?;
+                let format = options.format.unwrap_or_default();
+                print!(
+                    "{}",
+                    count_table(&selection, topic == "orders").render(format)
+                );
+            }
+            Some(other) => {
+                return Err(Error::new(ErrorKind::GenericCli(format!(
+                    "can't list {other}, try floral list orders, or floral list families"
+                ))))
+            }
+        },
+        Command::Search => {
+            let query = names.join(" ").to_lowercase();
+            if query.is_empty() && !options.filters.is_some() {
+                return Err(Error::new(ErrorKind::GenericCli(
+                    "give something to search for, e.g. floral search lili, or a filter".into(),
+                )));
+            }
+            let selection: Vec<_> = select(data, &[], true, &options)?
+                .into_iter()
+                .filter(|e| {
+                    e.order.to_lowercase().contains(&query)
+                        || e.family.to_lowercase().contains(&query)
+                })
+                .collect();
+            print_formulae(&selection, &options);
+        }
+        Command::Export => {
+            let selection = select(data, &names, cli_all || names.is_empty(), &options)?;
+            let format = options.format.unwrap_or(OutputFormat::Tsv);
+            print!("{}", data_table(&selection, &options).render(format));
+        }
+        Command::Compare => {
+            if names.len() < 2 {
+                return Err(Error::new(ErrorKind::GenericCli(
+                    "give two or more families (or orders, with -o) to compare, e.g. floral compare liliaceae iridaceae".into(),
+                )));
+            }
+            let selection = select(data, &names, false, &options)?;
+            let format = options.format.unwrap_or_default();
+            print!("{}", compare_table(&selection, &options).render(format));
+        }
+        Command::Stats => {
+            let selection = select(data, &names, names.is_empty(), &options)?;
+            let stats = Stats::new(
+                selection
+                    .iter()
+                    .map(|e| (e.order.as_str(), e.family.as_str(), &e.formula)),
+            );
+            let format = options.format.unwrap_or_default();
+            print!("{}", render_tables(&stats.tables(), format));
+        }
+        Command::Quiz => {
+            let selection = select(data, &names, names.is_empty(), &options)?;
+            run_quiz(data, &selection, &options, &quiz_options)?;
+        }
+    }
+
+    Ok(())
+}
+
+// the formulae asked for by name (or all of them), which pass the filters. Each
+// comes once, in the order of the database, however many times it was asked for.
+pub(crate) fn select(
+    data: &Data,
+    names: &[String],
+    all: bool,
+    options: &CliOptions,
+) -> Result<Vec<DataFormatter>> {
+    let data_keys: Vec<_> = if options.cli_order {
         data.keys().map(|(o, _, _)| o.to_string()).collect()
     } else {
         data.keys().map(|(_, f, _)| f.to_string()).collect()
     };
 
-    let input_str: Result<String> = match pargs.free_from_str::<String>() {
-        Ok(input_s) => Ok(input_s),
-        Err(_) => {
-            if cli_all {
-                Ok("".into()) // don't matter what this string is, it isn't used
-            } else {
-                print!("{}", generate_help_str());
-                std::process::exit(0);
+    let mut wanted = Vec::new();
+    for name in names {
+        if let Some((edit_dist, fo_string)) = did_you_mean(&data_keys, name) {
+            // so we don't do unexpected things on the cli
+            if edit_dist >= 4 {
+                return Err(Error::new(ErrorKind::GenericCli(format!(
+                    "you typed {name}, did you mean {fo_string}? Or something else?"
+                ))));
             }
+            wanted.push(fo_string);
         }
+    }
+
+    Ok(data
+        .iter()
+        .filter(|((order, family, _), formula)| {
+            let name = if options.cli_order { order } else { family };
+            (all || wanted.iter().any(|e| e == name)) && options.filters.passes(formula)
+        })
+        .map(|((order, family, ft), formula)| {
+            DataFormatter::new(
+                options,
+                order.to_string(),
+                family.to_string(),
+                ft.clone(),
+                formula.clone(),
+            )
+        })
+        .collect())
+}
+
+// print out the formulae in the format given on the cli
+fn print_formulae(selection: &[DataFormatter], options: &CliOptions) {
+    match options.format.unwrap_or_default() {
+        OutputFormat::Text => {
+            for formatter in selection {
+                formatter.print();
+            }
+        }
+        OutputFormat::Markdown => {
+            // markdown has a heading for each order, above its families
+            let mut last_order: Option<&str> = None;
+            for formatter in selection {
+                if last_order != Some(&formatter.order) {
+                    println!("## {}\n", formatter.order);
+                    last_order = Some(&formatter.order);
+                }
+                formatter.print_markdown();
+            }
+        }
+        format => print!("{}", data_table(selection, options).render(format)),
+    }
+}
+
+// the columns of the data behind each formula
+const DATA_HEADER: [&str; 11] = [
+    "order",
+    "family",
+    "flower_type",
+    "symmetry",
+    "tepals",
+    "sepals",
+    "petals",
+    "stamens",
+    "carpels",
+    "fruit",
+    "seed",
+];
+
+// the data behind the formulae, one row each, with the
+// explanation in the last column if one was asked for
+fn data_table(selection: &[DataFormatter], options: &CliOptions) -> Table {
+    let mut header = DATA_HEADER.to_vec();
+    if options.cli_explain {
+        header.push("explanation");
+    }
+    let mut table = Table::new(&header);
+    for formatter in selection {
+        let mut row = formatter.data_row();
+        if options.cli_explain {
+            let explained = formatter
+                .formula
+                .explain_with(formatter.locale, formatter.verbosity);
+            row.push(explained.into());
+        }
+        table.push_row(row);
+    }
+    table
+}
+
+// the tally of an order or a family for `count_table`
+#[derive(Default)]
+struct Count<'a> {
+    order: &'a str,
+    family: &'a str,
+    families: usize,
+    entries: usize,
+    staminate: bool,
+    carpellate: bool,
+}
+
+// the orders (or families) of the formulae, in the order of the database, with
+// the number of flower types of each and whether any are unisexual
+pub(crate) fn count_table(selection: &[DataFormatter], orders: bool) -> Table {
+    let mut table = if orders {
+        Table::new(&["order", "families", "entries", "staminate", "carpellate"])
+    } else {
+        Table::new(&["order", "family", "entries", "staminate", "carpellate"])
     };
+    let mut counts: Vec<Count> = Vec::new();
+    for formatter in selection {
+        let (order, family) = (formatter.order.as_str(), formatter.family.as_str());
+        let new_family = counts
+            .last()
+            .map_or(true, |e| e.order != order || e.family != family);
+        let new_row = match counts.last() {
+            Some(last) if orders => last.order != order,
+            _ => new_family,
+        };
+        if new_row {
+            counts.push(Count {
+                order,
+                family,
+                ..Default::default()
+            });
+        }
+        if let Some(count) = counts.last_mut() {
+            if new_family {
+                count.family = family;
+                count.families += 1;
+            }
+            count.entries += 1;
+            let ft = formatter.flower_type.get_flower_type();
+            count.staminate |= ft == FlowerType::Staminate;
+            count.carpellate |= ft == FlowerType::Carpellate;
+        }
+    }
+
+    for count in counts {
+        let name: Value = if orders {
+            count.families.into()
+        } else {
+            some_kind_of_uppercase_first_letter(count.family).into()
+        };
+        table.push_row(vec![
+            count.order.into(),
+            name,
+            count.entries.into(),
+            count.staminate.into(),
+            count.carpellate.into(),
+        ]);
+    }
+    table
+}
+
+// the data behind the formulae side by side, one column each,
+// with the merosity and androecium added in
+fn compare_table(selection: &[DataFormatter], options: &CliOptions) -> Table {
+    let labels: Vec<String> = selection
+        .iter()
+        .map(|e| {
+            format!(
+                "{} ({})",
+                some_kind_of_uppercase_first_letter(&e.family),
+                e.flower_type
+            )
+        })
+        .collect();
+    let mut header = vec!["field"];
+    header.extend(labels.iter().map(|e| e.as_str()));
+    let mut table = Table::new(&header);
+
+    let rows: Vec<_> = selection.iter().map(|e| e.data_row()).collect();
+    for (idx, field) in DATA_HEADER.iter().enumerate() {
+        let mut row: Vec<Value> = vec![(*field).into()];
+        row.extend(rows.iter().map(|e| e[idx].clone()));
+        table.push_row(row);
+    }
+    let mut merosity: Vec<Value> = vec!["merosity".into()];
+    merosity.extend(
+        selection
+            .iter()
+            .map(|e| e.formula.merosity().to_string().into()),
+    );
+    table.push_row(merosity);
+    let mut androecium: Vec<Value> = vec!["androecium".into()];
+    androecium.extend(
+        selection
+            .iter()
+            .map(|e| e.formula.androecium().to_string().into()),
+    );
+    table.push_row(androecium);
+    if options.cli_explain {
+        let mut explanation: Vec<Value> = vec!["explanation".into()];
+        explanation.extend(
+            selection
+                .iter()
+                .map(|e| e.formula.explain_with(e.locale, Verbosity::Brief).into()),
+        );
+        table.push_row(explanation);
+    }
+    table
+}
+
+// ask the questions one by one, or write them out with the answers
+fn run_quiz(
+    data: &Data,
+    selection: &[DataFormatter],
+    options: &CliOptions,
+    quiz_options: &QuizOptions,
+) -> Result<()> {
+    let locale = options.locale;
+    let pool: Vec<_> = selection
+        .iter()
+        .map(|e| (e.order.as_str(), e.family.as_str(), &e.formula))
+        .collect();
+    let all: Vec<_> = data
+        .iter()
+        .map(|((order, family, _), formula)| (*order, *family, formula))
+        .collect();
+    // without a seed, any will do, but it's shown so the quiz can be set again
+    let seed = quiz_options.seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|e| e.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    let mut rng = Rng::new(seed);
+    let questions = quiz::generate(
+        &pool,
+        &all,
+        &quiz_options.kinds,
+        quiz_options.number,
+        locale,
+        &mut rng,
+    );
+    if questions.is_empty() {
+        return Err(Error::new(ErrorKind::GenericCli(
+            "no questions could be made from these families, try some more".into(),
+        )));
+    }
+
+    let format = options.format.unwrap_or_default();
+    if let Some(sheet) = &quiz_options.sheet {
+        let mut out = quiz::question_sheet(&questions, seed, locale, format);
+        // a table has the answers in it already
+        if !format.is_tabular() {
+            let key = quiz::answer_key(&questions, seed, locale, format);
+            match &quiz_options.key {
+                Some(path) => write_out(path, &key)?,
+                None => out += &format!("\n{}", key),
+            }
+        }
+        return write_out(sheet, &out);
+    }
 
+    println!("{}", t!(locale, "Floral formula quiz (seed {})", seed));
+    let mut lines = io::stdin().lock().lines();
+    let mut score = 0;
+    let mut asked = 0;
+    'questions: for (idx, question) in questions.iter().enumerate() {
+        println!(
+            "\n{}\n{}",
+            t!(locale, "Question {} of {}", idx + 1, questions.len()),
+            question.get_prompt()
+        );
+        let choices = question.get_choices();
+        for (choice_idx, choice) in choices.iter().enumerate() {
+            println!("   {}) {}", quiz::choice_letter(choice_idx), choice);
+        }
+        let last = quiz::choice_letter(choices.len() - 1);
+        let choice = loop {
+            print!("{}", t!(locale, "Your answer (a-{}, or q to quit): ", last));
+            io::stdout().flush().ok();
+            let line = match lines.next() {
+                Some(Ok(line)) => line.trim().to_lowercase(),
+                _ => break 'questions,
+            };
+            if line == "q" {
+                break 'questions;
+            }
+            if let Some(choice) =
+                (0..choices.len()).find(|e| quiz::choice_letter(*e).to_string() == line)
+            {
+                break choice;
+            }
+        };
+        asked += 1;
+        if question.is_correct(choice) {
+            score += 1;
+            println!("{}", t!(locale, "Right!"));
+        } else {
+            let answer = question.get_answer();
+            println!(
+                "{}",
+                t!(
+                    locale,
+                    "Wrong, the answer is {}) {}",
+                    quiz::choice_letter(answer),
+                    choices[answer]
+                )
+            );
+        }
+    }
+    println!("\n{}", t!(locale, "You scored {} out of {}", score, asked));
+    Ok(())
+}
+
+// write to a file, or to the terminal for `-`
+fn write_out(path: &str, contents: &str) -> Result<()> {
+    if path == "-" {
+        print!("{}", contents);
+        return Ok(());
+    }
+    std::fs::write(path, contents).map_err(|e| {
+        Error::new(ErrorKind::GenericCli(format!(
+            "couldn't write to {path}: {e}"
+        )))
+    })
+}
+
+// explain a formula typed out on the command line, rather
+// than one from the database
+// explain a formula typed out in the notation, or the formulae of a
+// family or order named instead, as `show -e` would with the same flags
+fn explain(args: Vec<OsString>, verbosity: Verbosity, data: &Data) -> Result<()> {
+    let mut pargs = pico_args::Arguments::from_vec(args);
+    let order = pargs.contains(["-o", "--order"]);
+    // explaining is what the command does anyway
+    let _ = pargs.contains(["-e", "--explain"]);
+    let locale = pargs.opt_value_from_fn(["-l", "--lang"], Locale::from_str)?;
+    let format = pargs.opt_value_from_fn("--format", OutputFormat::from_str)?;
     let rest = pargs.finish();
-    let mut input = vec![input_str?];
 
-    let inputs = match rest.is_empty() {
-        true => input,
-        false => {
-            let mut rest: Vec<String> = rest
-                .into_iter()
-                .map(|e| e.into_string().unwrap_or_default())
-                .collect();
+    let name = match rest.as_slice() {
+        [name] => name.to_str(),
+        _ => None,
+    };
+    match name.and_then(|e| name_kind(e, data)) {
+        Some(is_order) => {
+            let mut show = vec![
+                "show".into(),
+                rest[0].clone(),
+                format!("--explain={}", verbosity).into(),
+            ];
+            if order || is_order {
+                show.push("-o".into());
+            }
+            if let Some(locale) = locale {
+                show.extend(["--lang".into(), locale.to_string().into()]);
+            }
+            if let Some(format) = format {
+                show.extend(["--format".into(), format.to_string().into()]);
+            }
+            run(show, data)
+        }
+        None => explain_notation(
+            rest,
+            locale.unwrap_or_default(),
+            format.unwrap_or_default(),
+            verbosity,
+        ),
+    }
+}
 
-            input.append(&mut rest);
-            input
+fn explain_notation(
+    args: Vec<OsString>,
+    locale: Locale,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> Result<()> {
+    let notation = match args.first().and_then(|e| e.to_str()) {
+        Some(notation) => notation.to_string(),
+        None => {
+            return Err(Error::new(ErrorKind::GenericCli(
+                "give a formula to explain, e.g. floral explain '*,K(5),C(5),A5,G_(2);capsule'"
+                    .into(),
+            )))
+        }
+    };
+    let formula = Formula::from_str(&notation)?;
+    match format {
+        OutputFormat::Text => println!("{}", formula.explain_with(locale, verbosity)),
+        OutputFormat::Markdown => print!("{}", formula.explain_markdown(locale, verbosity)),
+        format => {
+            let mut table = Table::new(&["formula", "explanation"]);
+            table.push_row(vec![
+                notation.into(),
+                formula.explain_with(locale, verbosity).into(),
+            ]);
+            print!("{}", table.render(format));
         }
+    }
+    Ok(())
+}
+
+// look up a term in the glossary, by name, by symbol, or by
+// the closest name. With no term, print the whole glossary.
+fn print_glossary(args: Vec<OsString>) -> Result<()> {
+    let mut pargs = pico_args::Arguments::from_vec(args);
+    let format = pargs
+        .opt_value_from_fn("--format", OutputFormat::from_str)?
+        .unwrap_or_default();
+    let term: Option<String> = pargs.opt_free_from_str()?;
+    let entries = match term {
+        Some(t) => lookup_glossary(&t)?,
+        None => glossary::entries().iter().collect(),
     };
 
-    if print_data {
-        println!("order\tfamily\tflower_type\tsymmetry\ttepals\tsepals\tpetals\tstamens\tcarpels\tfruit\n");
+    if format != OutputFormat::Text {
+        let mut table = Table::new(&["term", "symbol", "see", "definition"]);
+        for entry in entries {
+            table.push_row(vec![
+                entry.get_term().into(),
+                entry.get_symbol().unwrap_or_default().into(),
+                entry.get_see().join(";").into(),
+                entry.get_definition().into(),
+            ]);
+        }
+        print!("{}", table.render(format));
+        return Ok(());
     }
-    for input_str in inputs.clone().into_iter() {
-        if let Some((edit_dist, fo_string)) = did_you_mean(&data_keys, &input_str) {
-            // so we don't do unexpected things on the cli
-            if edit_dist >= 4 && !input_str.is_empty() {
+    for entry in entries {
+        println!("{}\n", format_glossary_entry(entry));
+    }
+    Ok(())
+}
+
+// the glossary entries for a term, a symbol, or the closest term
+fn lookup_glossary(term: &str) -> Result<Vec<&'static glossary::Entry>> {
+    let mut entries = match glossary::lookup(term) {
+        Some(entry) => vec![entry],
+        None => glossary::lookup_symbol(term),
+    };
+    if entries.is_empty() {
+        let terms: Vec<String> = glossary::entries()
+            .iter()
+            .map(|e| e.get_term().to_string())
+            .collect();
+        match did_you_mean(&terms, term) {
+            // short terms are close to everything, so go by the length too
+            Some((edit_dist, closest)) if edit_dist < 4 && edit_dist * 2 < term.len() => {
+                entries.extend(glossary::lookup(&closest));
+            }
+            Some((_, closest)) => {
                 return Err(Error::new(ErrorKind::GenericCli(format!(
-                    "you typed {input_str}, did you mean {fo_string}? Or something else?"
-                ))));
+                    "{term} is not in the glossary, did you mean {closest}? Or something else?"
+                ))))
             }
-
-            for ((order, family, ft), formula) in data.clone().into_iter() {
-                let formatter = DataFormatter::new(
-                    cli_all,
-                    cli_order,
-                    cli_explain,
-                    fo_string.clone(),
-                    order.to_string(),
-                    family.to_string(),
-                    ft,
-                    formula,
-                );
-                if print_data {
-                    formatter.print_tsv();
-                } else {
-                    formatter.print();
-                }
+            None => {
+                return Err(Error::new(ErrorKind::GenericCli(
+                    "the glossary is empty".into(),
+                )))
             }
         }
     }
+    Ok(entries)
+}
 
-    Ok(())
+// a glossary entry, with the definition and related terms indented below the term
+fn format_glossary_entry(entry: &glossary::Entry) -> String {
+    let options = Options::new(70)
+        .initial_indent("\t")
+        .subsequent_indent("\t");
+    let term = match entry.get_symbol() {
+        Some(symbol) => format!("{} ({})", entry.get_term(), symbol),
+        None => entry.get_term().to_string(),
+    };
+    let mut out = format!(
+        "{}\n{}",
+        term,
+        textwrap::wrap(entry.get_definition(), options).join("\n")
+    );
+    if !entry.get_see().is_empty() {
+        out += &format!("\n\tSee also: {}", entry.get_see().join(", "));
+    }
+    out
+}
+
+// print a completion script, or the names which the scripts complete
+fn print_completions(args: Vec<OsString>, data: &Data) -> Result<()> {
+    let mut pargs = pico_args::Arguments::from_vec(args);
+    if let Some(kind) = pargs.opt_value_from_str::<_, String>("--names")? {
+        for name in completions::names(&kind, data)? {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+    match pargs.opt_free_from_fn(Shell::from_str)? {
+        Some(shell) => {
+            print!("{}", completions::script(shell));
+            Ok(())
+        }
+        None => Err(Error::new(ErrorKind::GenericCli(
+            "give a shell to complete in, e.g. floral completions bash".into(),
+        ))),
+    }
+}
+
+// `--explain` can take a level of detail as `--explain=full`, which pico-args
+// can't tell apart from a plain `--explain` flag, so take it out beforehand
+fn take_explain_level(args: &mut Vec<OsString>) -> Result<Option<Verbosity>> {
+    let mut level = None;
+    let mut rest = Vec::new();
+    for arg in args.drain(..) {
+        match arg.to_str().and_then(|e| e.strip_prefix("--explain=")) {
+            Some(l) => level = Some(Verbosity::from_str(l)?),
+            None => rest.push(arg),
+        }
+    }
+    *args = rest;
+    Ok(level)
+}
+
+// the filters which can be applied to the data on the cli
+#[derive(Clone)]
+struct Filters {
+    inflorescence: Option<Inflorescence>,
+    fruit: Option<FruitQuery>,
+    merosity: Option<Merosity>,
+    androecium: Option<Androecium>,
+}
+
+impl Filters {
+    // whether any filter is set at all
+    fn is_some(&self) -> bool {
+        self.inflorescence.is_some()
+            || self.fruit.is_some()
+            || self.merosity.is_some()
+            || self.androecium.is_some()
+    }
+
+    // whether a formula passes all of the filters
+    fn passes(&self, formula: &Formula) -> bool {
+        let inflorescence = match self.inflorescence {
+            Some(i) => formula.get_inflorescence().contains(&i),
+            None => true,
+        };
+        let fruit = match &self.fruit {
+            Some(q) => formula.get_fruit().iter().any(|e| q.matches(e)),
+            None => true,
+        };
+        let merosity = match self.merosity {
+            Some(m) => formula.merosity() == m,
+            None => true,
+        };
+        let androecium = match self.androecium {
+            Some(a) => formula.androecium() == a,
+            None => true,
+        };
+        inflorescence && fruit && merosity && androecium
+    }
 }
 
 // gather together all the data we need to print things out properly to the terminal
-struct DataFormatter {
-    cli_all: bool,
-    cli_order: bool,
+pub(crate) struct DataFormatter {
     cli_explain: bool,
-    fo_string: String,
+    locale: Locale,
+    verbosity: Verbosity,
     order: String,
     family: String,
-    flower_type: FlowerType,
+    flower_type: Flower,
     formula: Formula,
 }
 
 impl DataFormatter {
-    #[allow(clippy::too_many_arguments)]
     fn new(
-        cli_all: bool,
-        cli_order: bool,
-        cli_explain: bool,
-        fo_string: String,
+        options: &CliOptions,
         order: String,
         family: String,
-        flower_type: FlowerType,
+        flower_type: Flower,
         formula: Formula,
     ) -> Self {
         Self {
-            cli_all,
-            cli_order,
-            cli_explain,
-            fo_string,
+            cli_explain: options.cli_explain,
+            locale: options.locale,
+            verbosity: options.verbosity,
             order,
             family,
             flower_type,
@@ -158,46 +996,81 @@ impl DataFormatter {
         }
     }
 
-    fn print(&self) {
-        let format_formula = |order: String,
-                              family: String,
-                              ft: FlowerType,
-                              formula: Formula,
-                              explain: bool|
-         -> String {
-            if explain {
-                let explained = formula.explain();
-                format!("{order} -> {family}\n{explained}")
-            } else {
-                format!("{order} -> {family} -> {ft}\n{formula}")
-            }
-        };
+    // the inflorescences, if there are any, e.g. ` [raceme, panicle]`
+    fn format_inflorescence(&self) -> String {
+        if self.formula.get_inflorescence().is_empty() {
+            return "".to_string();
+        }
+        format!(
+            " [{}]",
+            self.formula
+                .get_inflorescence()
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    // a heading for the family, the formula in a code span, and the
+    // explanation as nested bullets
+    fn print_markdown(&self) {
         let family = some_kind_of_uppercase_first_letter(&self.family);
-        let formatted = format_formula(
-            self.order.clone(),
+        let formula = self.formula.to_string();
+        let formula = formula.lines().next().unwrap_or_default();
+        let mut out = format!(
+            "### {} -> {}{}\n\n`{}`\n",
             family,
             self.flower_type,
-            self.formula.clone(),
-            self.cli_explain,
+            self.format_inflorescence(),
+            formula
         );
-        if self.cli_all {
-            println!("{}\n", formatted);
+        if self.cli_explain {
+            out += "\n";
+            out += &self.formula.explain_markdown(self.locale, self.verbosity);
         }
-        if self.cli_order {
-            if self.fo_string == self.order {
-                println!("{}\n", formatted);
+        println!("{}", out);
+    }
+
+    fn print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Display for DataFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let order = &self.order;
+        let family = some_kind_of_uppercase_first_letter(&self.family);
+        let ft = &self.flower_type;
+        let formula = &self.formula;
+        let mut formatted = if self.cli_explain && self.verbosity == Verbosity::Brief {
+            let summary = formula.explain_with(self.locale, self.verbosity);
+            format!("{order} -> {family} -> {ft}: {summary}")
+        } else if self.cli_explain {
+            let explained = formula.explain_with(self.locale, self.verbosity);
+            // morphs of the same family are told apart by name
+            match ft.get_morph() {
+                Some(morph) => {
+                    let morph_explained = ft.explain_in(self.locale);
+                    format!("{order} -> {family} -> {morph}\n{morph_explained}\n{explained}")
+                }
+                None => format!("{order} -> {family}\n{explained}"),
             }
-        } else if self.fo_string == self.family {
-            println!("{}\n", formatted);
+        } else {
+            let inflorescence = self.format_inflorescence();
+            format!("{order} -> {family} -> {ft}{inflorescence}\n{formula}")
+        };
+        // brief explanations are one line each
+        if !(self.cli_explain && self.verbosity == Verbosity::Brief) {
+            formatted.push('\n');
         }
+        write!(f, "{}", formatted)
     }
+}
 
-    fn print_tsv(&self) {
-        let mut out = String::new();
-        // sort out the variables
-        let order = &self.order;
-        let family = &self.family;
-        let flower_type = self.flower_type;
+impl DataFormatter {
+    // the data behind the formula, in the columns of `DATA_HEADER`
+    fn data_row(&self) -> Vec<Value> {
         let symmetry = self
             .formula
             .get_symmetry()
@@ -205,11 +1078,12 @@ impl DataFormatter {
             .map(|e| e.to_string())
             .collect::<Vec<String>>()
             .join(",");
-        let tepals = self.formula.get_tepals().clone().unwrap_or_default();
-        let sepals = self.formula.get_sepals().clone().unwrap_or_default();
-        let petals = self.formula.get_petals().clone().unwrap_or_default();
-        let stamens = self.formula.get_stamens().clone().unwrap_or_default();
-        let carpels = self.formula.get_carpels().clone().unwrap_or_default();
+        let part = |part: &Option<crate::floral::FloralPart>| -> Value {
+            part.as_ref()
+                .map(|e| e.to_string())
+                .unwrap_or_default()
+                .into()
+        };
         let fruit = self
             .formula
             .get_fruit()
@@ -217,19 +1091,26 @@ impl DataFormatter {
             .map(|e| e.to_string())
             .collect::<Vec<String>>()
             .join(",");
+        let seed = self
+            .formula
+            .get_seed()
+            .as_ref()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
 
-        out += &format!("{order}\t{family}\t{flower_type}\t{symmetry}\t{tepals}\t{sepals}\t{petals}\t{stamens}\t{carpels}\t{fruit}");
-
-        if self.cli_all {
-            println!("{}", out);
-        }
-        if self.cli_order {
-            if self.fo_string == self.order {
-                println!("{}", out);
-            }
-        } else if self.fo_string == self.family {
-            println!("{}", out);
-        }
+        vec![
+            self.order.clone().into(),
+            self.family.clone().into(),
+            self.flower_type.to_string().into(),
+            symmetry.into(),
+            part(self.formula.get_tepals()),
+            part(self.formula.get_sepals()),
+            part(self.formula.get_petals()),
+            part(self.formula.get_stamens()),
+            part(self.formula.get_carpels()),
+            fruit.into(),
+            seed.into(),
+        ]
     }
 }
 
@@ -252,14 +1133,14 @@ fn did_you_mean(possibilities: &[String], tried: &str) -> Option<(usize, String)
 }
 
 fn levenshtein_distance(a: &str, b: &str) -> usize {
-    lev_distance(a, b, usize::max_value()).unwrap_or(usize::max_value())
+    lev_distance(a, b, usize::MAX).unwrap_or(usize::MAX)
 }
 
 // see https://github.com/nushell/nushell/blob/99329f14a3db6945771725d65b1b553563ce6b28/crates/nu-protocol/src/lev_distance.rs#L57
 fn lev_distance(a: &str, b: &str, limit: usize) -> Option<usize> {
     let n = a.chars().count();
     let m = b.chars().count();
-    let min_dist = if n < m { m - n } else { n - m };
+    let min_dist = n.abs_diff(m);
 
     if min_dist > limit {
         return None;
@@ -300,3 +1181,103 @@ fn some_kind_of_uppercase_first_letter(s: &str) -> String {
         Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain_family_or_order_with_flags() {
+        let data = crate::parse::parse_data().unwrap();
+
+        // explain takes a family or order name as well as a formula
+        assert_eq!(name_kind("Saxifragaceae", &data), Some(false));
+        assert_eq!(name_kind("liliales", &data), Some(true));
+        assert_eq!(name_kind("*,K5,C5,A5,G3", &data), None);
+        let explain = |args: &[&str]| {
+            let mut args: Vec<_> = args.iter().map(|e| e.into()).collect();
+            args.insert(0, "explain".into());
+            run(args, &data)
+        };
+        assert!(explain(&["saxifragaceae"]).is_ok());
+        assert!(explain(&["saxifragales"]).is_ok());
+        assert!(explain(&["saxifragaceaes"]).is_err());
+        // with flags either side of the name, or of a formula
+        assert!(explain(&["--lang", "es", "liliaceae"]).is_ok());
+        assert!(explain(&["liliaceae", "--lang", "es"]).is_ok());
+        assert!(explain(&["-o", "liliales"]).is_ok());
+        assert!(explain(&["-e", "--format", "markdown", "liliaceae"]).is_ok());
+        assert!(explain(&["-l", "de", "*,K5,C5,A5,G3"]).is_ok());
+        assert!(explain(&["-l", "xx", "liliaceae"]).is_err());
+        assert!(explain(&["-o"]).is_err());
+    }
+
+    #[test]
+    fn select_entries() {
+        // inflorescences in the header, and as a filter
+        let data = crate::parse::parse_data().unwrap();
+        let options = |args: &[&str]| {
+            let args = args.iter().map(|e| e.into()).collect();
+            CliOptions::parse(&mut pico_args::Arguments::from_vec(args), None).unwrap()
+        };
+        let selection = select(&data, &["alliaceae".into()], false, &options(&[])).unwrap();
+        assert!(selection[0]
+            .to_string()
+            .starts_with("Asparagales -> Alliaceae -> Bisexual [umbel]\n"));
+
+        // the filter is case-insensitive, and leaves only the umbels
+        let umbels = select(&data, &[], true, &options(&["-i", "Umbel"])).unwrap();
+        assert!(!umbels.is_empty());
+        assert!(umbels.iter().all(|e| e.to_string().contains("umbel")));
+        assert!(umbels.len() < select(&data, &[], true, &options(&[])).unwrap().len());
+
+        // names with -a don't repeat any entry in the full list
+        let all = select(&data, &["asteraceae".into()], true, &options(&[])).unwrap();
+        assert_eq!(all.len(), data.len());
+        let args = vec!["-a".into(), "asteraceae".into()];
+        assert!(run(args, &data).is_ok());
+    }
+
+    #[test]
+    fn count_orders_and_families() {
+        // the orders and families listed, with their counts
+        let data = crate::parse::parse_data().unwrap();
+        let options = |args: &[&str]| {
+            let args = args.iter().map(|e| e.into()).collect();
+            CliOptions::parse(&mut pico_args::Arguments::from_vec(args), None).unwrap()
+        };
+        let liliales = select(&data, &["liliales".into()], false, &options(&["-o"])).unwrap();
+        assert_eq!(
+            count_table(&liliales, true).render(OutputFormat::Tsv),
+            "order\tfamilies\tentries\tstaminate\tcarpellate\nLiliales\t4\t4\tyes\tno\n"
+        );
+        let families = count_table(&liliales, false).render(OutputFormat::Tsv);
+        assert_eq!(families.lines().count(), 5);
+        assert!(families.contains("\nLiliales\tColchicaceae\t1\tno\tno\n"));
+        assert!(families.ends_with("\nLiliales\tSmilacaceae\t1\tyes\tno\n"));
+    }
+
+    #[test]
+    fn help_lists_every_flag_and_command() {
+        // the help lists the same flags and commands, and no others
+        let help = generate_help_str();
+        let (commands, flags) = help.split_once("\nFLAGS:\n").unwrap();
+        let mut help_flags: Vec<String> = flags
+            .lines()
+            .filter(|e| e.starts_with("  -"))
+            .flat_map(|e| e[..24].split(", ").map(|e| e.trim().to_string()))
+            .collect();
+        let mut all_flags: Vec<String> = FLAGS.iter().flat_map(|e| e.forms()).collect();
+        help_flags.sort();
+        all_flags.sort();
+        assert_eq!(help_flags, all_flags);
+        let (_, commands) = commands.split_once("COMMANDS:\n").unwrap();
+        let help_commands: Vec<&str> = commands
+            .lines()
+            .filter(|e| e.starts_with("  ") && !e.starts_with("   "))
+            .filter_map(|e| e.split_whitespace().next())
+            .collect();
+        let all_commands: Vec<&str> = COMMANDS.iter().map(|e| e.get_name()).collect();
+        assert_eq!(help_commands, all_commands);
+    }
+}
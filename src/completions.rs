@@ -0,0 +1,736 @@
+use crate::cli::Data;
+use crate::error::{Error, ErrorKind, Result};
+use crate::glossary;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The shells which completion scripts can be written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shell::Bash => write!(f, "bash"),
+            Shell::Zsh => write!(f, "zsh"),
+            Shell::Fish => write!(f, "fish"),
+        }
+    }
+}
+
+impl FromStr for Shell {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "shell: {}, not recognised (try bash, zsh or fish)",
+                other
+            )))),
+        }
+    }
+}
+
+/// What follows a flag on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagValue {
+    /// Nothing, the flag is a switch
+    None,
+    /// One of a few values
+    Choice(&'static [&'static str]),
+    /// The path to a file
+    File,
+    /// Anything, e.g. a query or a number
+    Any,
+}
+
+/// The groups the flags are listed in, in the help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagGroup {
+    General,
+    Filter,
+    Quiz,
+    OldStyle,
+}
+
+impl FlagGroup {
+    /// All of the groups, in the order of the help
+    pub const ALL: [FlagGroup; 4] = [
+        FlagGroup::General,
+        FlagGroup::Filter,
+        FlagGroup::Quiz,
+        FlagGroup::OldStyle,
+    ];
+}
+
+impl Display for FlagGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagGroup::General => write!(f, "GENERAL FLAGS"),
+            FlagGroup::Filter => write!(f, "FILTERS"),
+            FlagGroup::Quiz => write!(f, "QUIZ FLAGS"),
+            FlagGroup::OldStyle => write!(f, "OLD-STYLE FLAGS"),
+        }
+    }
+}
+
+/// A flag of the command line, as completed in the shells
+/// and listed in the help.
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    short: Option<char>,
+    long: &'static str,
+    value: FlagValue,
+    group: FlagGroup,
+    help: &'static str,
+    details: &'static str,
+}
+
+impl Flag {
+    /// Get the short form of the flag, if any, without the dash
+    pub fn get_short(&self) -> Option<char> {
+        self.short
+    }
+    /// Get the long form of the flag, without the dashes
+    pub fn get_long(&self) -> &'static str {
+        self.long
+    }
+    /// Get what follows the flag
+    pub fn get_value(&self) -> FlagValue {
+        self.value
+    }
+    /// Get the group the flag is listed in
+    pub fn get_group(&self) -> FlagGroup {
+        self.group
+    }
+    /// Get the description of the flag
+    pub fn get_help(&self) -> &'static str {
+        self.help
+    }
+    /// Get the rest of the description, following on from the help,
+    /// which is only in the help, e.g. examples
+    pub fn get_details(&self) -> &'static str {
+        self.details
+    }
+    /// The forms of the flag, as they are typed, e.g. `-l` and `--lang`
+    pub fn forms(&self) -> Vec<String> {
+        let mut forms: Vec<String> = self.short.map(|e| format!("-{e}")).into_iter().collect();
+        forms.push(format!("--{}", self.long));
+        forms
+    }
+}
+
+// short for the table below
+const fn flag(
+    short: Option<char>,
+    long: &'static str,
+    value: FlagValue,
+    group: FlagGroup,
+    help: &'static str,
+    details: &'static str,
+) -> Flag {
+    Flag {
+        short,
+        long,
+        value,
+        group,
+        help,
+        details,
+    }
+}
+
+/// Every flag of the command line, as in the help.
+pub const FLAGS: &[Flag] = &[
+    flag(
+        Some('h'),
+        "help",
+        FlagValue::None,
+        FlagGroup::General,
+        "Prints help information",
+        "",
+    ),
+    flag(
+        Some('v'),
+        "version",
+        FlagValue::None,
+        FlagGroup::General,
+        "Print version information only",
+        "",
+    ),
+    flag(
+        Some('o'),
+        "order",
+        FlagValue::None,
+        FlagGroup::General,
+        "Search plant orders, not families",
+        "",
+    ),
+    flag(
+        Some('e'),
+        "explain",
+        FlagValue::None,
+        FlagGroup::General,
+        "Explain the floral formula",
+        ", optionally at a level of detail (--explain=brief|normal|full)",
+    ),
+    flag(
+        Some('l'),
+        "lang",
+        FlagValue::Choice(&["en", "es", "de", "fr"]),
+        FlagGroup::General,
+        "The language of the explanation",
+        " (en, es, de or fr; default en)",
+    ),
+    flag(
+        None,
+        "format",
+        FlagValue::Choice(&["text", "markdown", "tsv", "csv", "json"]),
+        FlagGroup::General,
+        "Print as text, markdown, tsv, csv or json",
+        " (default text)",
+    ),
+    flag(
+        None,
+        "db",
+        FlagValue::File,
+        FlagGroup::General,
+        "Read the database from this file",
+        ", in the same format, rather than the bundled one",
+    ),
+    flag(
+        Some('i'),
+        "inflorescence",
+        FlagValue::Any,
+        FlagGroup::Filter,
+        "Only families with this inflorescence type",
+        " (e.g. umbel)",
+    ),
+    flag(
+        Some('f'),
+        "fruit",
+        FlagValue::Any,
+        FlagGroup::Filter,
+        "Only families with fruits matching this query",
+        " (e.g. \"fleshy indehiscent\", or \"capsule\")",
+    ),
+    flag(
+        Some('m'),
+        "merosity",
+        FlagValue::Any,
+        FlagGroup::Filter,
+        "Only families with this merosity",
+        " (e.g. 3, or \"variable\")",
+    ),
+    flag(
+        None,
+        "androecium",
+        FlagValue::Any,
+        FlagGroup::Filter,
+        "Only families with this androecium",
+        " (e.g. diplostemonous, or polyandrous)",
+    ),
+    flag(
+        Some('n'),
+        "number",
+        FlagValue::Any,
+        FlagGroup::Quiz,
+        "The number of questions",
+        " (default 10)",
+    ),
+    flag(
+        None,
+        "seed",
+        FlagValue::Any,
+        FlagGroup::Quiz,
+        "Seed the questions, to ask the same ones again",
+        "",
+    ),
+    flag(
+        Some('t'),
+        "type",
+        FlagValue::Choice(&["family", "ovary", "fruit", "part"]),
+        FlagGroup::Quiz,
+        "Only these kinds of question",
+        ", separated by commas (family, ovary, fruit or part)",
+    ),
+    flag(
+        None,
+        "sheet",
+        FlagValue::File,
+        FlagGroup::Quiz,
+        "Write the questions to this file",
+        " (- for the terminal) rather than asking them",
+    ),
+    flag(
+        None,
+        "key",
+        FlagValue::File,
+        FlagGroup::Quiz,
+        "Write the answers to this file",
+        ", rather than at the end of the sheet",
+    ),
+    flag(
+        Some('a'),
+        "all",
+        FlagValue::None,
+        FlagGroup::OldStyle,
+        "The same as floral list",
+        "",
+    ),
+    flag(
+        Some('d'),
+        "data",
+        FlagValue::None,
+        FlagGroup::OldStyle,
+        "The same as floral export",
+        "",
+    ),
+];
+
+/// A command of the command line, as completed in the shells
+/// and listed in the help.
+#[derive(Debug, Clone, Copy)]
+pub struct Subcommand {
+    name: &'static str,
+    args: &'static str,
+    help: &'static str,
+    details: &'static str,
+}
+
+impl Subcommand {
+    /// Get the name of the command
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+    /// Get the arguments the command takes, e.g. `<NAME>...`
+    pub fn get_args(&self) -> &'static str {
+        self.args
+    }
+    /// Get what the command does
+    pub fn get_help(&self) -> &'static str {
+        self.help
+    }
+    /// Get the rest of the description, following on from the help,
+    /// which is only in the help, e.g. examples
+    pub fn get_details(&self) -> &'static str {
+        self.details
+    }
+}
+
+// short for the table below
+const fn command(
+    name: &'static str,
+    args: &'static str,
+    help: &'static str,
+    details: &'static str,
+) -> Subcommand {
+    Subcommand {
+        name,
+        args,
+        help,
+        details,
+    }
+}
+
+/// Every command of the command line, and what it does.
+pub const COMMANDS: &[Subcommand] = &[
+    command(
+        "show",
+        "<NAME>...",
+        "Print the formulae of families",
+        " (or orders, with -o)",
+    ),
+    command(
+        "list",
+        "[orders|families]",
+        "Print every formula, or list the orders or families",
+        ", with the number of flower types in the database for each, and whether any are \
+         staminate or carpellate (e.g. list families -o liliales)",
+    ),
+    command(
+        "search",
+        "[QUERY]",
+        "Print the formulae of families matching a query",
+        " in their family or order name, and/or which pass the filters",
+    ),
+    command(
+        "export",
+        "[NAME]...",
+        "Print the data behind the formulae as a table",
+        " (tsv unless --format is given), all of it if no name is given",
+    ),
+    command(
+        "compare",
+        "<NAME>...",
+        "Compare the formulae of two or more families",
+        " (or orders, with -o) side by side",
+    ),
+    command(
+        "stats",
+        "[NAME]...",
+        "Summarise the database",
+        ", or the families (or orders, with -o) named: symmetry, merosity, ovary positions, \
+         fruits, and a row per order",
+    ),
+    command(
+        "quiz",
+        "[NAME]...",
+        "Revise with multiple choice questions",
+        " on the families (or orders, with -o) named, or on the whole database",
+    ),
+    command(
+        "explain",
+        "<FORMULA>",
+        "Explain a formula typed out in the usual notation",
+        " (e.g. '*,K(5),C(5),A5,G_(2)'), or the formulae of a family or order",
+    ),
+    command(
+        "glossary",
+        "[TERM]",
+        "Define a term or a symbol",
+        " (e.g. staminode, or K), or list the whole glossary",
+    ),
+    command(
+        "shell",
+        "",
+        "Start an interactive shell",
+        ", with history and tab completion of family and order names",
+    ),
+    command(
+        "completions",
+        "<SHELL>",
+        "Print a completion script for bash, zsh or fish",
+        " (e.g. source <(floral completions bash))",
+    ),
+];
+
+/// The names which the scripts complete, one of `families`, `orders`
+/// or `terms` (in the glossary), taken from the database at the time
+/// of completion so that a database given with `--db` is completed too.
+pub fn names(kind: &str, data: &Data) -> Result<Vec<String>> {
+    let mut names: Vec<String> = match kind {
+        "families" => data.keys().map(|(_, f, _)| f.to_string()).collect(),
+        "orders" => data.keys().map(|(o, _, _)| o.to_string()).collect(),
+        "terms" => glossary::entries()
+            .iter()
+            .map(|e| e.get_term().to_string())
+            .collect(),
+        other => {
+            return Err(Error::new(ErrorKind::FromStr(format!(
+                "names: {}, not recognised (try families, orders or terms)",
+                other
+            ))))
+        }
+    };
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// The completion script for a shell.
+pub fn script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash(),
+        Shell::Zsh => zsh(),
+        Shell::Fish => fish(),
+    }
+}
+
+// the flags followed by a value, as a shell pattern, e.g. `-l|--lang|--format`
+fn value_pattern(value: impl Fn(FlagValue) -> bool) -> String {
+    FLAGS
+        .iter()
+        .filter(|e| value(e.value))
+        .flat_map(|e| e.forms())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+// the cases which complete the value after a flag; `choice` and `file`
+// make the completion of each
+fn value_cases(choice: impl Fn(&[&str]) -> String, file: &str, any: &str) -> String {
+    let mut cases = String::new();
+    for f in FLAGS {
+        if let FlagValue::Choice(values) = f.value {
+            cases += &format!("        {}) {} ;;\n", f.forms().join("|"), choice(values));
+        }
+    }
+    cases += &format!(
+        "        {}) {} ;;\n",
+        value_pattern(|e| e == FlagValue::File),
+        file
+    );
+    cases += &format!(
+        "        {}) {} ;;\n",
+        value_pattern(|e| e == FlagValue::Any),
+        any
+    );
+    cases
+}
+
+fn command_names() -> String {
+    COMMANDS
+        .iter()
+        .map(|e| e.name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const BASH: &str = r#"# bash completion for floral, e.g. in ~/.bashrc:
+#   source <(floral completions bash)
+
+_floral() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local command="" kind=families db=() i word words
+
+    # the command, and whether orders or another database are asked for
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        word="${COMP_WORDS[i]}"
+        case "$word" in
+            --db) db=(--db "${COMP_WORDS[i+1]}"); ((i++)) ;;
+            -o|--order) kind=orders ;;
+            @VALUE_FLAGS@) ((i++)) ;;
+            -*) ;;
+            *) [[ -z "$command" ]] && command="$word" ;;
+        esac
+    done
+
+    case "$prev" in
+@VALUE_CASES@    esac
+
+    if [[ "$cur" == -* ]]; then
+        words="@FLAGS@"
+    else
+        case "$command" in
+            "") words="@COMMANDS@ $(_floral_names $kind "${db[@]}")" ;;
+            list) words="orders families" ;;
+            glossary) words="$(_floral_names terms)" ;;
+            completions) words="bash zsh fish" ;;
+            shell) words="" ;;
+            *) words="$(_floral_names $kind "${db[@]}")" ;;
+        esac
+    fi
+    # orders are capitalised, so match without regard to case
+    COMPREPLY=()
+    for word in $words; do
+        [[ "${word,,}" == "${cur,,}"* ]] && COMPREPLY+=("$word")
+    done
+}
+
+_floral_names() {
+    "${COMP_WORDS[0]}" completions --names "$@" 2>/dev/null
+}
+
+complete -F _floral floral
+"#;
+
+fn bash() -> String {
+    let flags: Vec<String> = FLAGS.iter().flat_map(|e| e.forms()).collect();
+    let cases = value_cases(
+        |values| {
+            format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return",
+                values.join(" ")
+            )
+        },
+        "COMPREPLY=($(compgen -f -- \"$cur\")); return",
+        "return",
+    );
+    BASH.replace("@VALUE_FLAGS@", &value_pattern(|e| e != FlagValue::None))
+        .replace("@VALUE_CASES@", &cases)
+        .replace("@FLAGS@", &flags.join(" "))
+        .replace("@COMMANDS@", &command_names())
+}
+
+const ZSH: &str = r#"#compdef floral
+
+# zsh completion for floral, e.g. in ~/.zshrc:
+#   source <(floral completions zsh)
+# or saved as _floral in a directory of $fpath
+
+_floral() {
+    local -a commands flags names db
+    local command kind=families word i
+
+    commands=(
+@COMMANDS@    )
+    flags=(
+@FLAGS@    )
+
+    # the command, and whether orders or another database are asked for
+    for ((i = 2; i < CURRENT; i++)); do
+        word=${words[i]}
+        case $word in
+            --db) db=(--db ${words[i+1]}); ((i++)) ;;
+            -o|--order) kind=orders ;;
+            @VALUE_FLAGS@) ((i++)) ;;
+            -*) ;;
+            *) [[ -z $command ]] && command=$word ;;
+        esac
+    done
+
+    case ${words[CURRENT-1]} in
+@VALUE_CASES@    esac
+
+    if [[ ${words[CURRENT]} == -* ]]; then
+        _describe -t flags 'flag' flags
+        return
+    fi
+    case $command in
+        '')
+            _describe -t commands 'command' commands
+            names=(${(f)"$(${words[1]} completions --names $kind $db 2>/dev/null)"})
+            compadd -M 'm:{a-zA-Z}={A-Za-z}' -- $names
+            ;;
+        list) compadd -- orders families ;;
+        glossary)
+            names=(${(f)"$(${words[1]} completions --names terms 2>/dev/null)"})
+            compadd -- $names
+            ;;
+        completions) compadd -- bash zsh fish ;;
+        shell) ;;
+        *)
+            names=(${(f)"$(${words[1]} completions --names $kind $db 2>/dev/null)"})
+            compadd -M 'm:{a-zA-Z}={A-Za-z}' -- $names
+            ;;
+    esac
+}
+
+if [[ $funcstack[1] == _floral ]]; then
+    _floral "$@"
+else
+    compdef _floral floral
+fi
+"#;
+
+fn zsh() -> String {
+    let commands: String = COMMANDS
+        .iter()
+        .map(|e| format!("        '{}:{}'\n", e.name, e.help))
+        .collect();
+    let flags: String = FLAGS
+        .iter()
+        .flat_map(|f| f.forms().into_iter().map(move |form| (form, f.help)))
+        .map(|(form, help)| format!("        '{}:{}'\n", form, help))
+        .collect();
+    let cases = value_cases(
+        |values| format!("compadd -- {}; return", values.join(" ")),
+        "_files; return",
+        "return",
+    );
+    ZSH.replace("@VALUE_FLAGS@", &value_pattern(|e| e != FlagValue::None))
+        .replace("@VALUE_CASES@", &cases)
+        .replace("@FLAGS@", &flags)
+        .replace("@COMMANDS@", &commands)
+}
+
+const FISH: &str = r#"# fish completion for floral, e.g.
+#   floral completions fish > ~/.config/fish/completions/floral.fish
+
+# the families, orders (with -o) or glossary terms, from the database
+# given with --db if there is one
+function __floral_names
+    set -l args (commandline -opc)
+    set -l kind $argv[1]
+    set -l db
+    for i in (seq 2 (count $args))
+        switch $args[$i]
+            case -o --order
+                test $kind = families; and set kind orders
+            case --db
+                test $i -lt (count $args); and set db --db $args[(math $i + 1)]
+        end
+    end
+    $args[1] completions --names $kind $db 2>/dev/null
+end
+
+set -l commands @COMMANDS@
+complete -c floral -f
+"#;
+
+fn fish() -> String {
+    let mut script = FISH.replace("@COMMANDS@", &command_names());
+    for command in COMMANDS {
+        script += &format!(
+            "complete -c floral -n \"not __fish_seen_subcommand_from $commands\" -a {} -d '{}'\n",
+            command.name, command.help
+        );
+    }
+    script += "complete -c floral -n \"not __fish_seen_subcommand_from list glossary shell completions\" -a '(__floral_names families)'\n";
+    script += "complete -c floral -n '__fish_seen_subcommand_from list' -a 'orders families'\n";
+    script += "complete -c floral -n '__fish_seen_subcommand_from glossary' -a '(__floral_names terms)'\n";
+    script +=
+        "complete -c floral -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish'\n";
+    for f in FLAGS {
+        let mut line = String::from("complete -c floral");
+        if let Some(short) = f.short {
+            line += &format!(" -s {}", short);
+        }
+        line += &format!(" -l {}", f.long);
+        match f.value {
+            FlagValue::None => (),
+            FlagValue::Choice(values) => line += &format!(" -x -a '{}'", values.join(" ")),
+            FlagValue::File => line += " -r -F",
+            FlagValue::Any => line += " -x",
+        }
+        line += &format!(" -d '{}'\n", f.help);
+        script += &line;
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_data_from, DATA};
+
+    #[test]
+    fn names_and_scripts() {
+        assert_eq!(Shell::from_str("zsh").unwrap(), Shell::Zsh);
+        assert!(Shell::from_str("tcsh").is_err());
+
+        // a database of one's own, in the same format
+        let mut lines = DATA.lines();
+        let header = lines.next().unwrap();
+        let row = lines.find(|e| e.contains("liliaceae")).unwrap();
+        let source = format!("{}\n{}\n", header, row.replace("liliaceae", "testaceae"));
+        let data = parse_data_from(&source).unwrap();
+        assert_eq!(names("families", &data).unwrap(), vec!["testaceae"]);
+        assert_eq!(names("orders", &data).unwrap(), vec!["Liliales"]);
+        assert!(names("terms", &data)
+            .unwrap()
+            .contains(&"tepal".to_string()));
+        assert!(names("genera", &data).is_err());
+
+        // every flag and command is in every script
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = script(shell);
+            for flag in FLAGS {
+                assert!(
+                    script.contains(flag.get_long()),
+                    "{} {}",
+                    shell,
+                    flag.get_long()
+                );
+            }
+            for command in COMMANDS {
+                assert!(script.contains(command.get_name()));
+            }
+            assert!(script.contains("completions --names"));
+            // names after explain, too
+            assert!(!script.contains("explain|shell"), "{}", shell);
+            assert!(!script.contains("from list explain"), "{}", shell);
+        }
+        // orders are capitalised, so names are matched without regard to case
+        assert!(script(Shell::Bash).contains("${word,,}"));
+        assert!(script(Shell::Zsh).contains("m:{a-zA-Z}={A-Za-z}"));
+    }
+}
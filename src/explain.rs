@@ -1,133 +1,475 @@
+use crate::analysis::{count_parts, Androecium};
+use crate::error::{Error, ErrorKind};
 use crate::floral::*;
+use crate::glossary;
+use crate::i18n::{format_message, t, Locale};
+use std::fmt::{self, Display};
+use std::str::FromStr;
 use textwrap::{self, Options};
 
 /// If the user wants an explanation of the floral parts
 pub trait ExplainFloralFormula {
-    /// The only method in this trait is to explain the
-    /// input as a string
-    fn explain(&self) -> String;
+    /// Explain the input as a string, in English
+    fn explain(&self) -> String {
+        self.explain_in(Locale::En)
+    }
+    /// Explain the input as a string, in the given language
+    fn explain_in(&self, locale: Locale) -> String;
+    /// Explain the input as a string, in the given language and
+    /// at the given level of detail. Only a whole formula has
+    /// different levels, everything else is explained as normal.
+    fn explain_with(&self, locale: Locale, _verbosity: Verbosity) -> String {
+        self.explain_in(locale)
+    }
+}
+
+/// How much detail to go into in an explanation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// A one line summary
+    Brief,
+    /// The usual explanation
+    #[default]
+    Normal,
+    /// The usual explanation, pointing to the glossary for the
+    /// terms used, with their definitions
+    Full,
+}
+
+impl Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verbosity::Brief => write!(f, "brief"),
+            Verbosity::Normal => write!(f, "normal"),
+            Verbosity::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "brief" => Ok(Self::Brief),
+            "normal" => Ok(Self::Normal),
+            "full" => Ok(Self::Full),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "explanation level: {}, not recognised (try brief, normal or full)",
+                other
+            )))),
+        }
+    }
 }
 
 impl ExplainFloralFormula for BilateralType {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            BilateralType::Up => format!("upwards bilateral (X({}))", self),
-            BilateralType::Down => format!("downward bilateral (X({}))", self),
-            BilateralType::Left => format!("left bilateral (X({}))", self),
-            BilateralType::Right => format!("right bilateral (X({}))", self),
-            BilateralType::Upleft => format!("up and left bilateral (X({}))", self),
-            BilateralType::Upright => format!("up and right bilateral (X({}))", self),
-            BilateralType::Downleft => format!("down and left bilateral (X({}))", self),
-            BilateralType::Downright => format!("down and right bilateral (X({}))", self),
+            BilateralType::Up => t!(locale, "upwards bilateral (X({}))", self),
+            BilateralType::Down => t!(locale, "downward bilateral (X({}))", self),
+            BilateralType::Left => t!(locale, "left bilateral (X({}))", self),
+            BilateralType::Right => t!(locale, "right bilateral (X({}))", self),
+            BilateralType::Upleft => t!(locale, "up and left bilateral (X({}))", self),
+            BilateralType::Upright => t!(locale, "up and right bilateral (X({}))", self),
+            BilateralType::Downleft => t!(locale, "down and left bilateral (X({}))", self),
+            BilateralType::Downright => t!(locale, "down and right bilateral (X({}))", self),
         }
     }
 }
 
 impl ExplainFloralFormula for FloralPartNumber {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            FloralPartNumber::Finite(num) => format!("{}", num),
+            FloralPartNumber::Finite(num) => num.to_string(),
             FloralPartNumber::Fractional(_) => "½".into(),
-            FloralPartNumber::Infinite => "infinite".into(),
+            FloralPartNumber::Infinite => t!(locale, "infinite"),
         }
     }
 }
 
 impl ExplainFloralFormula for FlowerType {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
             FlowerType::Bisexual => {
-                "A bisexual flower with both male (androecium) and female (gynoecium) parts.".into()
+                t!(
+                    locale,
+                    "A bisexual flower with both male (androecium) and female (gynoecium) parts."
+                )
             }
-            FlowerType::Carpellate => "A carpellate (female only) flower.".into(),
-            FlowerType::Staminate => "A staminate (male only) flower.".into(),
+            FlowerType::Carpellate => t!(locale, "A carpellate (female only) flower."),
+            FlowerType::Staminate => t!(locale, "A staminate (male only) flower."),
+        }
+    }
+}
+
+impl ExplainFloralFormula for Flower {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self.get_morph() {
+            Some(morph) => t!(
+                locale,
+                "{} This is the {} morph.",
+                self.get_flower_type().explain_in(locale),
+                morph
+            ),
+            None => self.get_flower_type().explain_in(locale),
         }
     }
 }
 
 impl ExplainFloralFormula for Fruit {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Fruit::Achene => t!(locale, "{} - small, dry, indehiscent, single seeded, thin walled", self),
+            Fruit::Berry => t!(locale, "{} - fleshy, indehiscent, one to many seeded, sometimes heterogeneous (i.e. inner fleshy, outer leathery)", self),
+            Fruit::Berrylets => t!(locale, "{} - as a berry, but an aggregate (i.e. developed from multiple carpels)", self),
+            Fruit::Capsule => t!(locale, "{} - dry (rarely fleshy), dehiscent, two to many seeded", self),
+            Fruit::Caryopsis => t!(locale, "{} - small, dry, indehiscent, with wall surrounding and fused to seed (grass specific)", self),
+            Fruit::Cypsela => t!(locale, "{} - as an achene, but from an inferior ovary, often crowned by a pappus (mainly Asteraceae)", self),
+            Fruit::DDrupe => t!(locale, "{} - fleshy, indehiscent, outer part soft to fibrous, breaking apart to reveal nut-like pits", self),
+            Fruit::Drupe => t!(locale, "{} - fleshy, indehiscent, with one or more hard pits", self),
+            Fruit::Drupelets => t!(locale, "{} - as a drupe, but an aggregate (i.e. developed from multiple carpels)", self),
+            Fruit::Follicle => t!(locale, "{} - dry to fleshy, from single carpel, releasing along a single longitudinal slit", self),
+            Fruit::Hesperidium => t!(locale, "{} - a berry with a leathery rind, and flesh made of juice-filled hairs (citrus)", self),
+            Fruit::IPod => t!(locale, "{} - dry, indehiscent, few to many seeds", self),
+            Fruit::Legume => t!(locale, "{} - dry, from single carpel that opens along two longitudinal slits (mainly legumes)", self),
+            Fruit::Loment => t!(locale, "{} - dry, from single carpel that transversely breaks into single seeded units", self),
+            Fruit::Mericarp => t!(locale, "{} - dry, indehiscent, one to two seeded unit split from a schizocarp", self),
+            Fruit::Nut => t!(locale, "{} - dry, indehiscent, large, with thick and bony wall around a single seed", self),
+            Fruit::AggregateOfNuts => t!(locale, "{} - as a nut, but an aggregate (i.e. developed from multiple carpels)", self),
+            Fruit::Pepo => t!(locale, "{} - a berry with a hard or leathery rind, from an inferior ovary (mainly Cucurbitaceae)", self),
+            Fruit::Pome => t!(locale, "{} - fleshy, indehiscent, with soft outer part, and papery structure around seeds", self),
+            Fruit::Samara => t!(locale, "{} - dry, indehiscent, winged, one to two seeds", self),
+            Fruit::Schizocarp => t!(locale, "{} - dry to fleshy, from two to many carpels that dehisces into mericarps (one to two seeded)", self),
+            Fruit::Silique => t!(locale, "{} - dehiscent, derived from two carpels, with two halves splitting from a partition", self),
+            Fruit::Strobile => t!(locale, "{} - a multiple fruit, cone-like, with seeds or fruits enclosed by persistent bracts", self),
+            Fruit::Syconium => t!(locale, "{} - a multiple fruit, fleshy, from an inside-out inflorescence with many achenes within (figs)", self),
+            Fruit::Utricle => t!(locale, "{} - dry, indehiscent, small, with thin wall that is loose and freen from a single seed", self),
+            Fruit::None => t!(locale, "{} - no fruit to describe", self),
+        }
+    }
+}
+
+impl ExplainFloralFormula for Accessory {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Accessory::Disc => t!(locale, "{} - a ring or cushion of nectar-secreting tissue, usually between the stamens and the ovary", self),
+            Accessory::Corona => t!(locale, "{} - a crown-like outgrowth between the perianth and the stamens", self),
+            Accessory::Nectaries => t!(locale, "{} - nectar-secreting glands on the perianth", self),
+            Accessory::SeptalNectaries => t!(locale, "{} - nectar-secreting glands in the walls (septa) between the locules of the ovary", self),
+            Accessory::StaminodialNectaries => t!(locale, "{} - sterile stamens (staminodes) which secrete nectar", self),
+            Accessory::Spur => t!(locale, "{} - a hollow, tubular extension of the perianth, usually holding nectar", self),
+        }
+    }
+}
+
+impl ExplainFloralFormula for Inflorescence {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Inflorescence::Capitulum => t!(locale, "{} - a dense head of sessile flowers on a flattened axis, surrounded by bracts", self),
+            Inflorescence::Catkin => t!(locale, "{} - a pendulous spike of small unisexual flowers, falling as a unit", self),
+            Inflorescence::Corymb => t!(locale, "{} - a raceme with lower pedicels longer than upper, so the flowers form a flat top", self),
+            Inflorescence::Cyathium => t!(locale, "{} - a cup of bracts containing a single carpellate flower and several staminate flowers", self),
+            Inflorescence::Cyme => t!(locale, "{} - a determinate inflorescence, the central flower opening first", self),
+            Inflorescence::Panicle => t!(locale, "{} - a branched raceme, with flowers on the branches", self),
+            Inflorescence::Raceme => t!(locale, "{} - an unbranched axis with stalked (pedicellate) flowers, the lower opening first", self),
+            Inflorescence::Solitary => t!(locale, "{} - a single flower, not in an inflorescence", self),
+            Inflorescence::Spadix => t!(locale, "{} - a spike with a fleshy axis, usually subtended by a spathe", self),
+            Inflorescence::Spike => t!(locale, "{} - an unbranched axis with sessile flowers", self),
+            Inflorescence::Spikelet => t!(locale, "{} - a small spike of flowers subtended by glumes (grasses and sedges)", self),
+            Inflorescence::Thyrse => t!(locale, "{} - a raceme-like main axis bearing lateral cymes", self),
+            Inflorescence::Umbel => t!(locale, "{} - flower stalks all arising from the same point, like the spokes of an umbrella", self),
+        }
+    }
+}
+
+impl ExplainFloralFormula for FruitDescription {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self.get_attributes() {
+            Some(a) if self.is_modified() => t!(
+                locale,
+                "{} - as a {}, but {}",
+                self,
+                self.get_fruit(),
+                a.get_pericarp()
+            ),
+            _ => self.get_fruit().explain_in(locale),
+        }
+    }
+}
+
+impl ExplainFloralFormula for Endosperm {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Endosperm::Copious => t!(locale, "{} - the embryo is surrounded by nutritive tissue (endosperm), which feeds it on germination", self),
+            Endosperm::Scant => t!(locale, "{} - only a thin layer of nutritive tissue is left, most food being stored in the embryo", self),
+            Endosperm::Absent => t!(locale, "{} - the endosperm is used up as the seed develops, and food is stored in the embryo, usually in the cotyledons", self),
+        }
+    }
+}
+
+impl ExplainFloralFormula for Seed {
+    fn explain_in(&self, locale: Locale) -> String {
+        let mut characters = Vec::new();
+        if let Some(endosperm) = self.get_endosperm() {
+            characters.push(endosperm.explain_in(locale));
+        }
+        if self.get_aril() {
+            characters.push(t!(locale, "arillate - the seed bears an aril, a fleshy and often brightly coloured outgrowth which attracts animals to disperse it"));
+        }
+        if self.get_winged() {
+            characters.push(t!(
+                locale,
+                "winged - the seed coat is drawn out into a wing, which aids dispersal by wind"
+            ));
+        }
+        if let Some(cotyledons) = self.get_cotyledons() {
+            characters.push(t!(
+                locale,
+                "the embryo has {} cotyledon(s) (seed leaves)",
+                cotyledons.explain_in(locale)
+            ));
+        }
+        characters.join("\n")
+    }
+}
+
+impl ExplainFloralFormula for Merosity {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            Fruit::Achene => format!("{} - small, dry, indehiscent, single seeded, thin walled", self),
-            Fruit::Berry => format!("{} - fleshy, indehiscent, one to many seeded, sometimes heterogeneous (i.e. inner fleshy, outer leathery)", self),
-            Fruit::Berrylets => format!("{} - as a berry, but an aggregate (i.e. developed from multiple carpels)", self),
-            Fruit::Capsule => format!("{} - dry (rarely fleshy), dehiscent, two to many seeded", self),
-            Fruit::Caryopsis => format!("{} - small, dry, indehiscent, with wall surrounding and fused to seed (grass specific)", self),
-            Fruit::DDrupe => format!("{} - fleshy, indehiscent, outer part soft to fibrous, breaking apart to reveal nut-like pits", self),
-            Fruit::Drupe => format!("{} - fleshy, indehiscent, with one or more hard pits", self),    
-            Fruit::Drupelets => format!("{} - as a drupe, but an aggregate (i.e. developed from multiple carpels)", self),
-            Fruit::Follicle => format!("{} - dry to fleshy, from single carpel, releasing along a single longitudinal slit", self),
-            Fruit::IPod => format!("{} - dry, indehiscent, few to many seeds", self),
-            Fruit::Legume => format!("{} - dry, from single carpel that opens along two longitudinal slits (mainly legumes)", self),
-            Fruit::Loment => format!("{} - dry, from single carpel that transversely breaks into single seeded units", self),
-            Fruit::Nut => format!("{} - dry, indehiscent, large, with thick and bony wall around a single seed", self),
-            Fruit::AggregateOfNuts => format!("{} - as a nut, but an aggregate (i.e. developed from multiple carpels)", self),
-            Fruit::Pome => format!("{} - fleshy, indehiscent, with soft outer part, and papery structure around seeds", self),
-            Fruit::Samara => format!("{} - dry, indehiscent, winged, one to two seeds", self),
-            Fruit::Schizocarp => format!("{} - dry to fleshy, from two to many carpels that dehisces into mericarps (one to two seeded)", self),
-            Fruit::Silique => format!("{} - dehiscent, derived from two carpels, with two halves splitting from a partition", self),
-            Fruit::Utricle => format!("{} - dry, indehiscent, small, with thin wall that is loose and freen from a single seed", self),
-            Fruit::None => format!("{} - no fruit to describe", self),
+            Merosity::Merous(n) => t!(
+                locale,
+                "The flower is {} (parts in multiples of {})",
+                self,
+                n
+            ),
+            Merosity::Variable => {
+                t!(locale, "The merosity is variable (the number of parts varies, or differs between whorls)")
+            }
+            Merosity::Indeterminate => {
+                t!(
+                    locale,
+                    "The merosity is indeterminate (there are no whorls to count)"
+                )
+            }
+        }
+    }
+}
+
+impl ExplainFloralFormula for Androecium {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Androecium::Haplostemonous => t!(locale, "The androecium is {} (one whorl of stamens, as many as the parts of a perianth whorl and alternating with the petals)", self),
+            Androecium::Obhaplostemonous => t!(locale, "The androecium is {} (one whorl of stamens, as many as the parts of a perianth whorl and opposite the petals)", self),
+            Androecium::Diplostemonous => t!(locale, "The androecium is {} (two whorls of stamens, the outer alternating with the petals)", self),
+            Androecium::Obdiplostemonous => t!(locale, "The androecium is {} (two whorls of stamens, the outer opposite the petals)", self),
+            Androecium::Polyandrous => t!(locale, "The androecium is {} (more stamens than two perianth whorls' worth)", self),
+            Androecium::Oligandrous => t!(locale, "The androecium is {} (fewer stamens than the parts of a perianth whorl)", self),
+            Androecium::Indeterminate => t!(locale, "The androecium is {} (the stamens can't be compared with the perianth)", self),
         }
     }
 }
 
 impl ExplainFloralFormula for Ovary {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Ovary::Superior => t!(locale, "a superior ovary"),
+            Ovary::Inferior => t!(locale, "an inferior ovary"),
+            Ovary::HalfInferior => {
+                t!(locale, "a half-inferior ovary (an intermediate position, partly sunk into the receptacle)")
+            }
+        }
+    }
+}
+
+impl ExplainFloralFormula for Placentation {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            Placentation::Axile => t!(
+                locale,
+                "{} - ovules attached to the central axis of an ovary divided by septa",
+                self
+            ),
+            Placentation::Parietal => t!(
+                locale,
+                "{} - ovules attached to the ovary wall, or to intrusions of it",
+                self
+            ),
+            Placentation::Basal => t!(
+                locale,
+                "{} - ovules attached at the base of the ovary",
+                self
+            ),
+            Placentation::FreeCentral => t!(
+                locale,
+                "{} - ovules attached to a free central column, with no septa",
+                self
+            ),
+            Placentation::Marginal => t!(
+                locale,
+                "{} - ovules attached along the margin of a single carpel",
+                self
+            ),
+            Placentation::Apical => {
+                t!(locale, "{} - ovules attached at the top of the ovary", self)
+            }
+            Placentation::Laminar => t!(
+                locale,
+                "{} - ovules scattered over the inner surface of the carpel wall",
+                self
+            ),
+        }
+    }
+}
+
+impl ExplainFloralFormula for CarpelFusion {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            Ovary::Superior => "a superior ovary".into(),
-            Ovary::Inferior => "an inferior ovary".into(),
-            Ovary::Both => "both superior and inferior ovaries".into(),
+            CarpelFusion::Apocarpous => t!(locale, "{} - carpels free from one another, each forming its own ovary", self),
+            CarpelFusion::Syncarpous => t!(locale, "{} - carpels fused into a single compound ovary, including the styles", self),
+            CarpelFusion::FreeStyles => t!(locale, "partly syncarpous ({}) - carpels fused into a single compound ovary, but with free styles", self),
+            CarpelFusion::UnitedStyles => t!(locale, "partly apocarpous ({}) - ovaries free from one another, but united by the styles or stigmas", self),
+        }
+    }
+}
+
+impl ExplainFloralFormula for StamenArrangement {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            StamenArrangement::Didynamous => {
+                t!(
+                    locale,
+                    "{} - four stamens, in two pairs of unequal length",
+                    self
+                )
+            }
+            StamenArrangement::Tetradynamous => {
+                t!(locale, "{} - six stamens, four long and two short", self)
+            }
+            StamenArrangement::Monadelphous => {
+                t!(
+                    locale,
+                    "{} - filaments fused into a single tube or column",
+                    self
+                )
+            }
+            StamenArrangement::Diadelphous => t!(
+                locale,
+                "{} - filaments fused into two groups, usually nine fused and one free (9+1)",
+                self
+            ),
+            StamenArrangement::Polyadelphous => {
+                t!(locale, "{} - filaments fused into several bundles", self)
+            }
+            StamenArrangement::Syngenesious => t!(
+                locale,
+                "{} - anthers fused into a tube around the style, with the filaments free",
+                self
+            ),
+            StamenArrangement::Epipetalous => {
+                t!(
+                    locale,
+                    "{} - stamens borne on the petals or the corolla tube",
+                    self
+                )
+            }
+        }
+    }
+}
+
+impl ExplainFloralFormula for ConnateOrgan {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            ConnateOrgan::Filaments => t!(
+                locale,
+                "Connation is of the {} - the stalks of the stamens are fused to one another",
+                self
+            ),
+            ConnateOrgan::Anthers => {
+                t!(
+                locale,
+                "Connation is of the {} - the anthers are fused to one another, usually into a tube around the style",
+                self
+            )
+            }
+            ConnateOrgan::Styles => t!(
+                locale,
+                "Connation is of the {} - the styles are fused into a single column",
+                self
+            ),
+            ConnateOrgan::Stigmas => t!(
+                locale,
+                "Connation is of the {} - the stigmas are fused into a single head",
+                self
+            ),
+        }
+    }
+}
+
+impl ExplainFloralFormula for PartCount {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            PartCount::Number(num) => num.explain_in(locale),
+            PartCount::Range(min, max) => {
+                t!(
+                    locale,
+                    "between {} and {}",
+                    min.explain_in(locale),
+                    max.explain_in(locale)
+                )
+            }
         }
     }
 }
 
 impl ExplainFloralFormula for Part {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            Part::Tepals => "tepals".into(),
-            Part::Calyx => "calyx".into(),
-            Part::Petals => "petals".into(),
-            Part::Stamens => "stamens".into(),
-            Part::Carpels => "carpels".into(),
+            Part::Epicalyx => t!(locale, "epicalyx"),
+            Part::Bracteoles => t!(locale, "bracteoles"),
+            Part::Tepals => t!(locale, "tepals"),
+            Part::Calyx => t!(locale, "calyx"),
+            Part::Petals => t!(locale, "petals"),
+            Part::Stamens => t!(locale, "stamens"),
+            Part::Carpels => t!(locale, "carpels"),
         }
     }
 }
 
 impl ExplainFloralFormula for Sterile {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            Sterile::Fertile => "fertile part".into(),
-            Sterile::Sterile => "sterile part".into(),
+            Sterile::Fertile => t!(locale, "fertile part"),
+            Sterile::Sterile => t!(locale, "sterile part"),
         }
     }
 }
 
 impl ExplainFloralFormula for Symmetry {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         match self {
-            Symmetry::Radial => format!("radial ({})", self),
-            Symmetry::Bilateral(b) => b.explain(),
-            Symmetry::Asymmetry => format!("asymmetrical ({})", self),
-            Symmetry::Spiral => format!("sprial ({})", self),
-            Symmetry::Disymmetric => format!("disymmetric ({})", self),
+            Symmetry::Radial => t!(locale, "radial ({})", self),
+            Symmetry::Bilateral(b) => b.explain_in(locale),
+            Symmetry::Asymmetry => t!(locale, "asymmetrical ({})", self),
+            Symmetry::Spiral => t!(locale, "sprial ({})", self),
+            Symmetry::Disymmetric => t!(locale, "disymmetric ({})", self),
         }
     }
 }
 
 impl ExplainFloralFormula for Adnation {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         let var = self.to_owned().get_variation();
         let parts = self.to_owned().get_parts();
 
         if let Some(inner_part_vec) = parts {
             let inner_parts = inner_part_vec
                 .iter()
-                .map(|e| e.explain())
+                .map(|e| e.explain_in(locale))
                 .collect::<Vec<String>>()
-                .join(", and ");
+                .join(&t!(locale, ", and "));
 
-            format!(
+            t!(
+                locale,
                 "Adnation between {} floral parts. {} between species.",
                 inner_parts,
                 if var.to_owned() {
@@ -137,13 +479,13 @@ impl ExplainFloralFormula for Adnation {
                 }
             )
         } else {
-            "There is no adnation between floral parts".into()
+            t!(locale, "There is no adnation between floral parts")
         }
     }
 }
 
 impl ExplainFloralFormula for FloralPart {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         // few more bits to this one.
         let part = self.get_part();
         let connation = match self.get_connation() {
@@ -158,54 +500,167 @@ impl ExplainFloralFormula for FloralPart {
         let mut whorls_string = String::new();
         for (mut idx, whorl) in whorls.iter().enumerate() {
             idx += 1;
-            whorls_string += "\tWhorl ";
-            whorls_string += &idx.to_string();
+            whorls_string += "\t";
+            whorls_string += &t!(locale, "Whorl {}", idx);
+            if let Some(position) = whorl.get_position() {
+                whorls_string += &format!(" ({})", t!(locale, &position.to_string()));
+            }
             whorls_string += ": ";
-            whorls_string += &whorl.explain();
+            whorls_string += &whorl.explain_in(locale);
             whorls_string += "\n";
         }
+        for organ in self.connate_organs_to_show() {
+            whorls_string += &format!("\t{}\n", organ.explain_in(locale));
+        }
 
         let ovary = match part {
             Part::Carpels => {
-                let ovary = self.get_ovary();
-                match ovary {
-                    Some(o) => format!("\tWhorl has {}", o.explain()),
-
-                    None => "".into(),
+                let mut ovary_string = match self.get_ovary().as_slice() {
+                    [] => "".into(),
+                    [o] => format!("\t{}", t!(locale, "Whorl has {}", o.explain_in(locale))),
+                    // not an intermediate position, but different ones in the group
+                    positions => {
+                        let positions = positions
+                            .iter()
+                            .map(|e| e.explain_in(locale))
+                            .collect::<Vec<String>>()
+                            .join(&t!(locale, ", or "));
+                        format!(
+                            "\t{}",
+                            t!(
+                                locale,
+                                "The ovary position varies in the group: {}",
+                                positions
+                            )
+                        )
+                    }
+                };
+                if self.get_hypanthium() {
+                    ovary_string += "\n\t";
+                    ovary_string += &t!(locale, "A hypanthium (floral cup) is present, bearing the perianth and stamens on its rim");
+                }
+                let placentation = self.get_placentation();
+                if !placentation.is_empty() {
+                    let placentation_string = placentation
+                        .iter()
+                        .map(|e| e.explain_in(locale))
+                        .collect::<Vec<String>>()
+                        .join(&t!(locale, ", or "));
+                    ovary_string += &format!(
+                        "\n\t{}",
+                        t!(locale, "Placentation is {}", placentation_string)
+                    );
+                }
+                if let Some(locules) = self.get_locules() {
+                    ovary_string += &format!(
+                        "\n\t{}",
+                        t!(locale, "Ovary has {} locule(s)", locules.explain_in(locale))
+                    );
+                }
+                if let Some(ovules) = self.get_ovules() {
+                    ovary_string += &format!(
+                        "\n\t{}",
+                        t!(
+                            locale,
+                            "Each locule has {} ovule(s)",
+                            ovules.explain_in(locale)
+                        )
+                    );
+                }
+                if let Some(carpel_fusion) = self.get_carpel_fusion() {
+                    ovary_string += &format!(
+                        "\n\t{}",
+                        t!(locale, "Gynoecium is {}", carpel_fusion.explain_in(locale))
+                    );
+                }
+                if let Some(stigmas) = self.get_stigmas() {
+                    ovary_string += &format!(
+                        "\n\t{}",
+                        t!(locale, "Stigma has {} lobe(s)", stigmas.explain_in(locale))
+                    );
+                }
+                ovary_string
+            }
+            Part::Stamens => {
+                let mut stamen_string = String::new();
+                for arrangement in self.get_stamen_arrangement() {
+                    stamen_string += &format!(
+                        "\t{}\n",
+                        t!(locale, "Stamens are {}", arrangement.explain_in(locale))
+                    );
                 }
+                stamen_string
             }
+            Part::Epicalyx => {
+                format!(
+                    "\t{}\n",
+                    t!(
+                        locale,
+                        "An epicalyx is a whorl of sepal-like bracts just outside the calyx"
+                    )
+                )
+            }
+            Part::Bracteoles => format!(
+                "\t{}\n",
+                t!(locale, "Bracteoles are small bracts on the flower stalk")
+            ),
             _ => "".into(),
         };
 
         format!(
-            "{} = {} are {} {}\n{}{}\n",
-            self,
-            part.explain(),
-            connation,
-            connation_variation,
+            "{}\n{}{}\n",
+            t!(
+                locale,
+                "{} = {} are {} {}",
+                self,
+                part.explain_in(locale),
+                connation,
+                connation_variation
+            ),
             whorls_string,
             ovary
         )
     }
 }
 
+impl ExplainFloralFormula for WhorlNature {
+    fn explain_in(&self, locale: Locale) -> String {
+        match self {
+            WhorlNature::Petaloid => t!(locale, "{} (coloured and petal-like)", self),
+            WhorlNature::Sepaloid => t!(locale, "{} (green or chaffy, and sepal-like)", self),
+        }
+    }
+}
+
 impl ExplainFloralFormula for Whorl {
-    fn explain(&self) -> String {
+    fn explain_in(&self, locale: Locale) -> String {
         let min = self.get_min();
         let max = self.get_max();
         let number = self.get_number();
-        let sterile = self.get_sterility().explain();
+        let sterile = self.get_sterility().explain_in(locale);
+        let nature = match self.get_nature() {
+            Some(n) => format!(", {}", n.explain_in(locale)),
+            None => "".into(),
+        };
 
         match (min, max, number) {
             (None, None, Some(num)) => {
-                format!("{} and has {} parts", sterile, num.explain())
+                t!(
+                    locale,
+                    "{} and has {} parts{}",
+                    sterile,
+                    num.explain_in(locale),
+                    nature
+                )
             }
             (Some(min_n), Some(max_n), None) => {
-                format!(
-                    "{} and has between {} and {} parts",
+                t!(
+                    locale,
+                    "{} and has between {} and {} parts{}",
                     sterile,
-                    min_n.explain(),
-                    max_n.explain()
+                    min_n.explain_in(locale),
+                    max_n.explain_in(locale),
+                    nature
                 )
             }
             _ => "INVALID WHORL - this is a BUG!".to_string(),
@@ -215,8 +670,31 @@ impl ExplainFloralFormula for Whorl {
 
 // the actual explanation!
 impl ExplainFloralFormula for Formula {
-    fn explain(&self) -> String {
+    fn explain_with(&self, locale: Locale, verbosity: Verbosity) -> String {
+        match verbosity {
+            Verbosity::Brief => summarise(self, locale),
+            Verbosity::Normal => self.explain_in(locale),
+            Verbosity::Full => {
+                let options = Options::new(70).subsequent_indent("\t");
+                let definitions = glossary_definitions(self, locale)
+                    .iter()
+                    .map(|e| textwrap::wrap(e, options.clone()).join("\n"))
+                    .collect::<Vec<String>>()
+                    .join("\n\t");
+                format!(
+                    "{}\n\n{}\n\n{}\n\t{}",
+                    self.explain_in(locale),
+                    textwrap::wrap(&glossary_links(self, locale), 70).join("\n"),
+                    t!(locale, "Glossary:"),
+                    definitions
+                )
+            }
+        }
+    }
+
+    fn explain_in(&self, locale: Locale) -> String {
         let symmetry = self.get_symmetry();
+        let outer = self.get_outer();
         let tepals = self.get_tepals();
         let sepals = self.get_sepals();
         let petals = self.get_petals();
@@ -224,64 +702,464 @@ impl ExplainFloralFormula for Formula {
         let carpels = self.get_carpels();
         let fruits = self.get_fruit();
         let adnation = self.get_adnation();
+        let accessory = self.get_accessory();
+        let inflorescence = self.get_inflorescence();
+        let seed = self.get_seed();
 
         let sym = symmetry
             .iter()
-            .map(|e| e.explain())
+            .map(|e| e.explain_in(locale))
             .collect::<Vec<String>>()
-            .join(" or ");
+            .join(&t!(locale, " or "));
 
-        let symmetry_string = format!("The symmetry is {}", sym);
+        let symmetry_string = t!(locale, "The symmetry is {}", sym);
+        let merosity_string = match self.androecium() {
+            Androecium::Indeterminate => self.merosity().explain_in(locale),
+            androecium => format!(
+                "{}\n{}",
+                self.merosity().explain_in(locale),
+                androecium.explain_in(locale)
+            ),
+        };
 
         // reduce a bit of boiler plate here...
-        fn explain_floral_part(fp: &Option<FloralPart>) -> String {
+        fn explain_floral_part(fp: &Option<FloralPart>, locale: Locale) -> String {
             if let Some(p) = fp {
-                p.explain()
+                p.explain_in(locale)
             } else {
                 "".into()
             }
         }
 
-        let tepal_string = explain_floral_part(tepals);
-        let sepal_string = explain_floral_part(sepals);
-        let petal_string = explain_floral_part(petals);
-        let stamen_string = explain_floral_part(stamens);
-        let carpel_string = explain_floral_part(carpels);
+        let outer_string = explain_floral_part(outer, locale);
+        let tepal_string = explain_floral_part(tepals, locale);
+        let sepal_string = explain_floral_part(sepals, locale);
+        let petal_string = explain_floral_part(petals, locale);
+        let stamen_string = explain_floral_part(stamens, locale);
+        let carpel_string = explain_floral_part(carpels, locale);
 
         // nicer format to have subsequent indents here.
         let options = Options::new(70).subsequent_indent("\t");
         let fruit_string_inner = fruits
             .iter()
-            .map(|e| textwrap::wrap(&e.explain(), options.clone()).join("\n"))
+            .map(|e| textwrap::wrap(&e.explain_in(locale), options.clone()).join("\n"))
             .collect::<Vec<String>>()
             .join("\n\t");
 
-        let fruit_string = format!("Fruit(s):\n\t{}", fruit_string_inner);
-        let adnation_string = adnation.explain();
+        let fruit_string = format!("{}\n\t{}", t!(locale, "Fruit(s):"), fruit_string_inner);
+
+        let inflorescence_string = if inflorescence.is_empty() {
+            "".into()
+        } else {
+            let inflorescence_string_inner = inflorescence
+                .iter()
+                .map(|e| textwrap::wrap(&e.explain_in(locale), options.clone()).join("\n"))
+                .collect::<Vec<String>>()
+                .join("\n\t");
+            format!(
+                "{}\n\t{}\n\n",
+                t!(locale, "Inflorescence(s):"),
+                inflorescence_string_inner
+            )
+        };
+
+        let accessory_string = if accessory.is_empty() {
+            "".into()
+        } else {
+            let accessory_string_inner = accessory
+                .iter()
+                .map(|e| textwrap::wrap(&e.explain_in(locale), options.clone()).join("\n"))
+                .collect::<Vec<String>>()
+                .join("\n\t");
+            format!(
+                "\n{}\n\t{}\n",
+                t!(locale, "Accessory structure(s):"),
+                accessory_string_inner
+            )
+        };
+        let seed_string = match seed {
+            Some(s) => {
+                let seed_string_inner = s
+                    .explain_in(locale)
+                    .lines()
+                    .map(|e| textwrap::wrap(e, options.clone()).join("\n"))
+                    .collect::<Vec<String>>()
+                    .join("\n\t");
+                format!("\n\n{}\n\t{}", t!(locale, "Seed(s):"), seed_string_inner)
+            }
+            None => "".into(),
+        };
+        let adnation_string = adnation.explain_in(locale);
 
         let out = format!(
             "\
 {formula}
 
-Explanation of floral formula above:
+{heading}
 
 {symmetry}
+{merosity}
 
-{tepals}{sepals}{petals}{stamens}{carpels}
-{fruits}
+{inflorescence}{outer}{tepals}{sepals}{petals}{stamens}{carpels}{accessory}
+{fruits}{seed}
 
 {adnation}",
             formula = self,
+            heading = t!(locale, "Explanation of floral formula above:"),
             symmetry = textwrap::wrap(&symmetry_string, 70).join("\n"),
+            merosity = merosity_string
+                .lines()
+                .map(|e| textwrap::wrap(e, 70).join("\n"))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            inflorescence = inflorescence_string,
+            outer = outer_string,
             tepals = tepal_string,
             sepals = sepal_string,
             petals = petal_string,
             stamens = stamen_string,
             carpels = carpel_string,
+            accessory = accessory_string,
             fruits = fruit_string,
+            seed = seed_string,
             adnation = adnation_string
         );
 
         out
     }
 }
+
+// a one line summary of a formula, e.g. radial, 5 connate petals, inferior ovary, berry
+fn summarise(formula: &Formula, locale: Locale) -> String {
+    let symmetry = formula
+        .get_symmetry()
+        .iter()
+        .map(|e| match e {
+            Symmetry::Radial => t!(locale, "radial"),
+            Symmetry::Bilateral(_) => t!(locale, "bilateral"),
+            Symmetry::Asymmetry => t!(locale, "asymmetrical"),
+            Symmetry::Spiral => t!(locale, "spiral"),
+            Symmetry::Disymmetric => t!(locale, "disymmetric"),
+        })
+        .collect::<Vec<String>>()
+        .join(&t!(locale, " or "));
+    let mut summary = vec![symmetry];
+
+    let parts = [
+        formula.get_outer(),
+        formula.get_tepals(),
+        formula.get_sepals(),
+        formula.get_petals(),
+        formula.get_stamens(),
+        formula.get_carpels(),
+    ];
+    for part in parts.into_iter().flatten() {
+        let number = match count_parts(part) {
+            Some((min, max)) if min.to_string() == max.to_string() => min.to_string(),
+            Some((min, max)) => format!("{}-{}", min, max),
+            None => continue,
+        };
+        if number == "0" {
+            continue;
+        }
+        // sepals reads better than calyx with a number
+        let name = match (part.get_part(), number == "1") {
+            (Part::Epicalyx, _) => t!(locale, "epicalyx"),
+            (Part::Bracteoles, true) => t!(locale, "bracteole"),
+            (Part::Tepals, true) => t!(locale, "tepal"),
+            (Part::Calyx, true) => t!(locale, "sepal"),
+            (Part::Calyx, false) => t!(locale, "sepals"),
+            (Part::Petals, true) => t!(locale, "petal"),
+            (Part::Stamens, true) => t!(locale, "stamen"),
+            (Part::Carpels, true) => t!(locale, "carpel"),
+            (other, false) => other.explain_in(locale),
+        };
+        if part.get_connation() {
+            summary.push(t!(locale, "{} connate {}", number, name));
+        } else {
+            summary.push(format!("{} {}", number, name));
+        }
+    }
+
+    if let Some(carpels) = formula.get_carpels() {
+        let positions: Vec<String> = carpels
+            .get_ovary()
+            .iter()
+            .map(|ovary| match ovary {
+                Ovary::Superior => t!(locale, "superior ovary"),
+                Ovary::HalfInferior => t!(locale, "half-inferior ovary"),
+                Ovary::Inferior => t!(locale, "inferior ovary"),
+            })
+            .collect();
+        if !positions.is_empty() {
+            summary.push(positions.join(&t!(locale, ", or ")));
+        }
+    }
+
+    let fruits = formula
+        .get_fruit()
+        .iter()
+        .filter(|e| !matches!(e.get_fruit(), Fruit::None))
+        .map(|e| t!(locale, &e.to_string()))
+        .collect::<Vec<String>>();
+    if !fruits.is_empty() {
+        summary.push(fruits.join(&t!(locale, " or ")));
+    }
+
+    summary.join(", ")
+}
+
+// the line pointing to the glossary for the terms used. these are
+// what the glossary is looked up by, so stay in English
+fn glossary_links(formula: &Formula, locale: Locale) -> String {
+    format_message(
+        locale,
+        "Terms in the glossary (floral glossary <term>): {}",
+        &[glossary_terms(formula).join(", ")],
+    )
+}
+
+// the definitions of the glossary terms used, with their related terms
+fn glossary_definitions(formula: &Formula, locale: Locale) -> Vec<String> {
+    glossary_terms(formula)
+        .into_iter()
+        .filter_map(glossary::lookup)
+        .map(|e| {
+            let mut entry = format!("{} - {}", e.get_term(), e.get_definition());
+            if !e.get_see().is_empty() {
+                entry += &format!(" ({})", t!(locale, "see also {}", e.get_see().join(", ")));
+            }
+            entry
+        })
+        .collect()
+}
+
+// the glossary terms which an explanation of a formula uses, in the order
+// they come up
+fn glossary_terms(formula: &Formula) -> Vec<&'static str> {
+    let mut terms = Vec::new();
+
+    for symmetry in formula.get_symmetry() {
+        terms.push(match symmetry {
+            Symmetry::Radial => "radial",
+            Symmetry::Bilateral(_) => "bilateral",
+            Symmetry::Asymmetry => "asymmetrical",
+            Symmetry::Spiral => "spiral",
+            Symmetry::Disymmetric => "disymmetric",
+        });
+    }
+    if let Merosity::Merous(_) = formula.merosity() {
+        terms.push("merosity");
+    }
+    if formula.androecium() != Androecium::Indeterminate {
+        terms.push("androecium");
+    }
+
+    let parts = [
+        formula.get_outer(),
+        formula.get_tepals(),
+        formula.get_sepals(),
+        formula.get_petals(),
+        formula.get_stamens(),
+        formula.get_carpels(),
+    ];
+    for part in parts.into_iter().flatten() {
+        terms.push(match part.get_part() {
+            Part::Epicalyx => "epicalyx",
+            Part::Bracteoles => "bracteole",
+            Part::Tepals => "tepal",
+            Part::Calyx => "calyx",
+            Part::Petals => "petal",
+            Part::Stamens => "stamen",
+            Part::Carpels => "carpel",
+        });
+        if part.get_connation() {
+            terms.push("connate");
+        }
+        for whorl in part.get_whorls() {
+            match whorl.get_nature() {
+                Some(WhorlNature::Petaloid) => terms.push("petaloid"),
+                Some(WhorlNature::Sepaloid) => terms.push("sepaloid"),
+                None => (),
+            }
+            if let Sterile::Sterile = whorl.get_sterility() {
+                match part.get_part() {
+                    Part::Stamens => terms.push("staminode"),
+                    Part::Carpels => terms.push("pistillode"),
+                    _ => (),
+                }
+            }
+        }
+        for ovary in part.get_ovary() {
+            terms.push(match ovary {
+                Ovary::Superior => "superior",
+                Ovary::HalfInferior => "half-inferior",
+                Ovary::Inferior => "inferior",
+            });
+        }
+        if part.get_hypanthium() {
+            terms.push("hypanthium");
+        }
+        if !part.get_placentation().is_empty() {
+            terms.push("placentation");
+        }
+        if part.get_locules().is_some() {
+            terms.push("locule");
+        }
+        if part.get_ovules().is_some() {
+            terms.push("ovule");
+        }
+        if part.get_stigmas().is_some() {
+            terms.push("stigma");
+        }
+    }
+
+    for fruit in formula.get_fruit() {
+        if let Some(attributes) = fruit.get_attributes() {
+            match attributes.get_dehiscence() {
+                Dehiscence::Dehiscent => terms.push("dehiscent"),
+                Dehiscence::Indehiscent | Dehiscence::Schizocarpic => terms.push("indehiscent"),
+            }
+        }
+    }
+    if let Some(seed) = formula.get_seed() {
+        if seed.get_endosperm().is_some() {
+            terms.push("endosperm");
+        }
+        if seed.get_cotyledons().is_some() {
+            terms.push("cotyledon");
+        }
+    }
+    if formula.get_adnation().to_owned().get_parts().is_some() {
+        terms.push("adnate");
+    }
+
+    // only the first time each term comes up
+    let mut seen = Vec::new();
+    terms.retain(|e| {
+        let first = !seen.contains(e);
+        seen.push(*e);
+        first
+    });
+    terms
+}
+
+impl Formula {
+    /// Explain the formula as a Markdown list, with a nested list for
+    /// the whorls of each floral part, the fruits and so on.
+    pub fn explain_markdown(&self, locale: Locale, verbosity: Verbosity) -> String {
+        if verbosity == Verbosity::Brief {
+            return format!("- {}\n", escape_markdown(&summarise(self, locale)));
+        }
+
+        let mut out = String::new();
+
+        let sym = self
+            .get_symmetry()
+            .iter()
+            .map(|e| e.explain_in(locale))
+            .collect::<Vec<String>>()
+            .join(&t!(locale, " or "));
+        out += &markdown_item(
+            &escape_markdown(&t!(locale, "The symmetry is {}", sym)),
+            &[],
+        );
+        out += &markdown_item(&escape_markdown(&self.merosity().explain_in(locale)), &[]);
+        if self.androecium() != Androecium::Indeterminate {
+            out += &markdown_item(&escape_markdown(&self.androecium().explain_in(locale)), &[]);
+        }
+
+        let inflorescence: Vec<_> = self
+            .get_inflorescence()
+            .iter()
+            .map(|e| e.explain_in(locale))
+            .collect();
+        if !inflorescence.is_empty() {
+            out += &markdown_item(
+                &escape_markdown(&t!(locale, "Inflorescence(s):")),
+                &inflorescence,
+            );
+        }
+
+        let parts = [
+            self.get_outer(),
+            self.get_tepals(),
+            self.get_sepals(),
+            self.get_petals(),
+            self.get_stamens(),
+            self.get_carpels(),
+        ];
+        for part in parts.into_iter().flatten() {
+            // the first line is about the part as a whole,
+            // the rest (tab indented) about its whorls and ovary
+            let explained = part.explain_in(locale);
+            let mut lines = explained.lines().filter(|e| !e.trim().is_empty());
+            let heading = lines.next().unwrap_or_default();
+            let nested: Vec<String> = lines.map(|e| e.trim().to_string()).collect();
+            // the part itself goes in a code span
+            let symbol = part.to_string();
+            match heading.strip_prefix(&symbol) {
+                Some(rest) => {
+                    let heading = format!("`{}`{}", symbol, escape_markdown(rest));
+                    out += &markdown_item(&heading, &nested);
+                }
+                None => out += &markdown_item(&escape_markdown(heading), &nested),
+            }
+        }
+
+        let accessory: Vec<_> = self
+            .get_accessory()
+            .iter()
+            .map(|e| e.explain_in(locale))
+            .collect();
+        if !accessory.is_empty() {
+            out += &markdown_item(
+                &escape_markdown(&t!(locale, "Accessory structure(s):")),
+                &accessory,
+            );
+        }
+        let fruits: Vec<_> = self
+            .get_fruit()
+            .iter()
+            .map(|e| e.explain_in(locale))
+            .collect();
+        out += &markdown_item(&escape_markdown(&t!(locale, "Fruit(s):")), &fruits);
+        if let Some(seed) = self.get_seed() {
+            let seed: Vec<_> = seed
+                .explain_in(locale)
+                .lines()
+                .map(|e| e.to_string())
+                .collect();
+            out += &markdown_item(&escape_markdown(&t!(locale, "Seed(s):")), &seed);
+        }
+        out += &markdown_item(
+            &escape_markdown(&self.get_adnation().explain_in(locale)),
+            &[],
+        );
+
+        if verbosity == Verbosity::Full {
+            out += &markdown_item(&escape_markdown(&glossary_links(self, locale)), &[]);
+            out += &markdown_item(
+                &escape_markdown(&t!(locale, "Glossary:")),
+                &glossary_definitions(self, locale),
+            );
+        }
+
+        out
+    }
+}
+
+// an item of a Markdown list, with its own nested list below. The
+// heading is Markdown already, the nested items are plain text.
+fn markdown_item(heading: &str, nested: &[String]) -> String {
+    let mut out = format!("- {}\n", heading);
+    for line in nested {
+        out += &format!("  - {}\n", escape_markdown(line));
+    }
+    out
+}
+
+// stop the symbols in an explanation being read as Markdown emphasis
+fn escape_markdown(s: &str) -> String {
+    s.replace('*', "\\*").replace('_', "\\_")
+}
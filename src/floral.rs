@@ -5,7 +5,7 @@ use std::str::FromStr;
 use crate::error::{Error, ErrorKind};
 
 /// The type of flower we're looking at
-#[derive(PartialEq, Hash, Eq, PartialOrd, Ord, Copy, Clone)]
+#[derive(Debug, PartialEq, Hash, Eq, PartialOrd, Ord, Copy, Clone)]
 pub enum FlowerType {
     /// Bisexual or perfect flowers
     Bisexual,
@@ -41,6 +41,48 @@ impl FromStr for FlowerType {
     }
 }
 
+/// A flower within a family. Most families are described by
+/// their [`FlowerType`] alone, but some have several named
+/// morphs on one plant (e.g. the ray and disc florets of the
+/// Asteraceae), each with their own formula.
+#[derive(Debug, PartialEq, Hash, Eq, PartialOrd, Ord, Clone)]
+pub struct Flower {
+    /// The sex of the flower
+    flower_type: FlowerType,
+    /// The name of the morph, if there is one
+    morph: Option<String>,
+}
+
+impl Flower {
+    /// Constructor for the [`Flower`] struct
+    pub fn new(flower_type: FlowerType, morph: Option<String>) -> Self {
+        Self { flower_type, morph }
+    }
+    /// Get the flower type
+    pub fn get_flower_type(&self) -> FlowerType {
+        self.flower_type
+    }
+    /// Get the name of the morph
+    pub fn get_morph(&self) -> &Option<String> {
+        &self.morph
+    }
+}
+
+impl From<FlowerType> for Flower {
+    fn from(flower_type: FlowerType) -> Self {
+        Self::new(flower_type, None)
+    }
+}
+
+impl Display for Flower {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.morph {
+            Some(morph) => write!(f, "{} ({})", self.flower_type, morph),
+            None => write!(f, "{}", self.flower_type),
+        }
+    }
+}
+
 /// The floral symmetry of a flower
 #[derive(Debug, Copy, Clone)]
 pub enum Symmetry {
@@ -241,6 +283,9 @@ impl Adnation {
 pub struct Formula {
     /// Floral symmetry
     symmetry: Vec<Symmetry>,
+    /// An outer whorl of bract-like organs, either an
+    /// epicalyx or bracteoles
+    outer: Option<FloralPart>,
     /// Tepals
     tepals: Option<FloralPart>,
     /// Sepals
@@ -252,9 +297,16 @@ pub struct Formula {
     /// Carpels
     carpels: Option<FloralPart>,
     /// Fruit
-    fruit: Vec<Fruit>,
+    fruit: Vec<FruitDescription>,
     /// Where is the adnation present?
     adnation: Adnation,
+    /// Accessory structures, such as a nectar disc
+    /// or a corona
+    accessory: Vec<Accessory>,
+    /// The inflorescence(s) the flower is borne in
+    inflorescence: Vec<Inflorescence>,
+    /// Seed and embryo characters
+    seed: Option<Seed>,
 }
 
 impl Formula {
@@ -263,6 +315,11 @@ impl Formula {
         self.symmetry = symmetry;
         self
     }
+    /// Constructor function for the outer whorl
+    pub fn with_outer(mut self, outer: Option<FloralPart>) -> Formula {
+        self.outer = outer;
+        self
+    }
     /// Constructor function for the tepals
     pub fn with_tepals(mut self, tepals: Option<FloralPart>) -> Formula {
         self.tepals = tepals;
@@ -289,7 +346,7 @@ impl Formula {
         self
     }
     /// Constructor function for the fruit
-    pub fn with_fruit(mut self, fruit: Vec<Fruit>) -> Formula {
+    pub fn with_fruit(mut self, fruit: Vec<FruitDescription>) -> Formula {
         self.fruit = fruit;
         self
     }
@@ -298,10 +355,26 @@ impl Formula {
         self.adnation = adnation;
         self
     }
+    /// Constructor function for the accessory structures
+    pub fn with_accessory(mut self, accessory: Vec<Accessory>) -> Formula {
+        self.accessory = accessory;
+        self
+    }
+    /// Constructor function for the inflorescence
+    pub fn with_inflorescence(mut self, inflorescence: Vec<Inflorescence>) -> Formula {
+        self.inflorescence = inflorescence;
+        self
+    }
+    /// Constructor function for the seed
+    pub fn with_seed(mut self, seed: Option<Seed>) -> Formula {
+        self.seed = seed;
+        self
+    }
     /// Build the floral formula. Might be redundant?
     pub fn build(self) -> Formula {
         Formula {
             symmetry: self.symmetry,
+            outer: self.outer,
             tepals: self.tepals,
             sepals: self.sepals,
             petals: self.petals,
@@ -309,6 +382,9 @@ impl Formula {
             carpels: self.carpels,
             fruit: self.fruit,
             adnation: self.adnation,
+            accessory: self.accessory,
+            inflorescence: self.inflorescence,
+            seed: self.seed,
         }
     }
     /// Whether the formula has adnation at all
@@ -319,6 +395,10 @@ impl Formula {
     pub fn get_symmetry(&self) -> &Vec<Symmetry> {
         &self.symmetry
     }
+    /// Get the outer whorl
+    pub fn get_outer(&self) -> &Option<FloralPart> {
+        &self.outer
+    }
     /// Get tepals
     pub fn get_tepals(&self) -> &Option<FloralPart> {
         &self.tepals
@@ -340,13 +420,129 @@ impl Formula {
         &self.carpels
     }
     /// Get fruit
-    pub fn get_fruit(&self) -> &Vec<Fruit> {
+    pub fn get_fruit(&self) -> &Vec<FruitDescription> {
         &self.fruit
     }
     /// Get adnation
     pub fn get_adnation(&self) -> &Adnation {
         &self.adnation
     }
+    /// Get the accessory structures
+    pub fn get_accessory(&self) -> &Vec<Accessory> {
+        &self.accessory
+    }
+    /// Get the inflorescence(s)
+    pub fn get_inflorescence(&self) -> &Vec<Inflorescence> {
+        &self.inflorescence
+    }
+    /// Get the seed characters
+    pub fn get_seed(&self) -> &Option<Seed> {
+        &self.seed
+    }
+    /// Infer the merosity (the base number of parts in a whorl)
+    /// from the whorls of the perianth, or from the androecium
+    /// if there is no perianth to go on.
+    pub fn merosity(&self) -> Merosity {
+        let perianth = [&self.tepals, &self.sepals, &self.petals];
+        match Merosity::from_parts(&perianth) {
+            Merosity::Indeterminate => Merosity::from_parts(&[&self.stamens]),
+            // an undivided perianth of six tepals is conventionally
+            // two whorls of three, e.g. T6 is T3+3
+            Merosity::Merous(6)
+                if self.sepals.is_none()
+                    && self.tepals.as_ref().is_some_and(|t| t.whorls.len() == 1) =>
+            {
+                Merosity::Merous(3)
+            }
+            m => m,
+        }
+    }
+}
+
+/// The merosity of a flower, or the base number of parts
+/// in its whorls, e.g. trimerous (3) in most monocots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Merosity {
+    /// The whorls are in multiples of this number
+    Merous(u32),
+    /// The number of parts varies, or the whorls share no base number
+    Variable,
+    /// There are no countable whorls to infer the merosity from
+    Indeterminate,
+}
+
+impl Merosity {
+    // the common base number of the finite whorls of some floral parts
+    pub(crate) fn from_parts(parts: &[&Option<FloralPart>]) -> Merosity {
+        fn gcd(a: u32, b: u32) -> u32 {
+            if b == 0 {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+
+        let mut counts = Vec::new();
+        for whorl in parts
+            .iter()
+            .filter_map(|e| e.as_ref())
+            .flat_map(|e| &e.whorls)
+        {
+            match (&whorl.number, &whorl.min, &whorl.max) {
+                (Some(FloralPartNumber::Finite(0)), _, _) => (),
+                (Some(FloralPartNumber::Finite(n)), _, _) => counts.push(*n),
+                // can't count these
+                (Some(FloralPartNumber::Infinite), _, _) => (),
+                _ => return Merosity::Variable,
+            }
+        }
+
+        match counts.iter().fold(0, |acc, e| gcd(acc, *e)) {
+            0 => Merosity::Indeterminate,
+            1 if counts.iter().all(|e| *e == 1) => Merosity::Indeterminate,
+            1 => Merosity::Variable,
+            n => Merosity::Merous(n),
+        }
+    }
+}
+
+impl Display for Merosity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Merosity::Merous(2) => write!(f, "dimerous"),
+            Merosity::Merous(3) => write!(f, "trimerous"),
+            Merosity::Merous(4) => write!(f, "tetramerous"),
+            Merosity::Merous(5) => write!(f, "pentamerous"),
+            Merosity::Merous(6) => write!(f, "hexamerous"),
+            Merosity::Merous(n) => write!(f, "{}-merous", n),
+            Merosity::Variable => write!(f, "variable"),
+            Merosity::Indeterminate => write!(f, "indeterminate"),
+        }
+    }
+}
+
+impl FromStr for Merosity {
+    type Err = Error;
+
+    // e.g. 3, or trimerous
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dimerous" => Ok(Self::Merous(2)),
+            "trimerous" => Ok(Self::Merous(3)),
+            "tetramerous" => Ok(Self::Merous(4)),
+            "pentamerous" => Ok(Self::Merous(5)),
+            "hexamerous" => Ok(Self::Merous(6)),
+            "variable" => Ok(Self::Variable),
+            "indeterminate" => Ok(Self::Indeterminate),
+            other => match other.trim_end_matches("-merous").parse::<u32>() {
+                Ok(n) if n > 1 => Ok(Self::Merous(n)),
+                _ => Err(Error::new(ErrorKind::FromStr(format!(
+                    "merosity: {}, not recognised",
+                    other
+                )))),
+            },
+        }
+    }
 }
 
 /// The information needed to render the adnation
@@ -356,6 +552,8 @@ struct AdnationIndex {
     /// Whether adnation is variable or not.
     /// Inherited from [`Adnation`]
     variation: bool,
+    /// Where the outer whorl index is
+    outer: Option<usize>,
     /// Where the tepals index is
     tepals: Option<usize>,
     /// Where the sepals index is
@@ -371,6 +569,7 @@ struct AdnationIndex {
 impl AdnationIndex {
     fn set_adnation_status(&mut self, part: Part, index: usize) {
         match part {
+            Part::Epicalyx | Part::Bracteoles => self.outer = Some(index),
             Part::Tepals => self.tepals = Some(index),
             Part::Calyx => self.sepals = Some(index),
             Part::Petals => self.petals = Some(index),
@@ -401,6 +600,7 @@ impl Display for AdnationIndex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // merge into a vec
         let merged = vec![
+            self.outer,
             self.tepals,
             self.sepals,
             self.petals,
@@ -466,7 +666,6 @@ impl Display for AdnationIndex {
                 }
                 write!(f, "{}", adnation)
             }
-            _ => Err(fmt::Error),
         }
     }
 }
@@ -513,6 +712,24 @@ impl Display for Formula {
             }
         }
 
+        // the outer whorl comes before the perianth
+        let outer = if let Some(o) = &self.outer {
+            let outer_string = format!(",{}", o);
+
+            update_adnation_vec_and_format_index(
+                &adnation_vec,
+                o.get_part(),
+                o,
+                format_index,
+                &mut adnation_status,
+            );
+            format_index += outer_string.chars().count();
+
+            outer_string
+        } else {
+            "".into()
+        };
+
         let calyx_perianth_or_tepals: String = match (&self.tepals, &self.petals, &self.sepals) {
             (None, None, None) => panic!("there should be at least one floral part"),
             (None, None, Some(_)) => panic!("petals should be specified if tepals are"),
@@ -642,6 +859,20 @@ impl Display for Formula {
             "".into()
         };
 
+        // accessory structures come after the gynoecium, in braces
+        let accessory = if self.accessory.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "{{{}}}",
+                self.accessory
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            )
+        };
+
         let fruits = &self
             .fruit
             .iter()
@@ -649,6 +880,11 @@ impl Display for Formula {
             .collect::<Vec<String>>()
             .join(",");
         let fruit_string = format!(";{}", fruits);
+        // the seed follows the fruit
+        let seed_string = match &self.seed {
+            Some(seed) => format!(";seed({})", seed),
+            None => "".to_string(),
+        };
         let adnation_string = if adnation_status.to_string().is_empty() {
             "".to_string()
         } else {
@@ -657,23 +893,44 @@ impl Display for Formula {
 
         write!(
             f,
-            "{}{}{}{}{}{}",
-            sym, calyx_perianth_or_tepals, anthers, carpels, fruit_string, adnation_string
+            "{}{}{}{}{}{}{}{}{}",
+            sym,
+            outer,
+            calyx_perianth_or_tepals,
+            anthers,
+            carpels,
+            accessory,
+            fruit_string,
+            seed_string,
+            adnation_string
         )
     }
 }
 
 /// An ovary can be inferior or
 /// superior. Though, there are in
-/// betweens.
-#[derive(Debug, Clone, Copy)]
+/// betweens. A group in which the position
+/// varies has more than one of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Ovary {
     /// A superior ovary
     Superior,
+    /// An ovary in an intermediate position,
+    /// partly sunk into the receptacle or hypanthium
+    HalfInferior,
     /// An inferior ovary
     Inferior,
-    /// Both
-    Both,
+}
+
+impl Ovary {
+    // the combining character under or over the G
+    fn mark(&self) -> char {
+        match self {
+            Ovary::Superior => '\u{0332}',
+            Ovary::HalfInferior => '\u{335}',
+            Ovary::Inferior => '\u{305}',
+        }
+    }
 }
 
 impl FromStr for Ovary {
@@ -683,6 +940,7 @@ impl FromStr for Ovary {
         match s {
             "s" => Ok(Self::Superior),
             "i" => Ok(Self::Inferior),
+            "h" => Ok(Self::HalfInferior),
             ov_str => Err(Error::new(ErrorKind::FromStr(format!(
                 "the string: {}, does not correspond to an ovary position",
                 ov_str
@@ -691,10 +949,300 @@ impl FromStr for Ovary {
     }
 }
 
+/// Placentation, the arrangement of the ovules
+/// within the ovary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placentation {
+    /// Ovules on the central axis of a
+    /// septate ovary
+    Axile,
+    /// Ovules on the ovary wall, or on
+    /// intrusions of it
+    Parietal,
+    /// Ovules at the base of the ovary
+    Basal,
+    /// Ovules on a central column, with
+    /// no septa
+    FreeCentral,
+    /// Ovules along the margin of a single
+    /// carpel
+    Marginal,
+    /// Ovules at the top of the ovary
+    Apical,
+    /// Ovules scattered over the inner
+    /// surface of the carpel wall
+    Laminar,
+}
+
+impl Display for Placentation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Placentation::Axile => write!(f, "axile"),
+            Placentation::Parietal => write!(f, "parietal"),
+            Placentation::Basal => write!(f, "basal"),
+            Placentation::FreeCentral => write!(f, "free-central"),
+            Placentation::Marginal => write!(f, "marginal"),
+            Placentation::Apical => write!(f, "apical"),
+            Placentation::Laminar => write!(f, "laminar"),
+        }
+    }
+}
+
+impl FromStr for Placentation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "axile" => Ok(Self::Axile),
+            "p" | "parietal" => Ok(Self::Parietal),
+            "b" | "basal" => Ok(Self::Basal),
+            "fc" | "free-central" => Ok(Self::FreeCentral),
+            "m" | "marginal" => Ok(Self::Marginal),
+            "ap" | "apical" => Ok(Self::Apical),
+            "l" | "laminar" => Ok(Self::Laminar),
+            pl_str => Err(Error::new(ErrorKind::FromStr(format!(
+                "the string: {}, does not correspond to a placentation",
+                pl_str
+            )))),
+        }
+    }
+}
+
+/// How the carpels of the gynoecium are fused
+/// to one another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CarpelFusion {
+    /// Carpels free from one another
+    Apocarpous,
+    /// Carpels fused, including the styles
+    Syncarpous,
+    /// Ovaries fused, but the styles free
+    FreeStyles,
+    /// Ovaries free, but united by the styles
+    /// or stigmas
+    UnitedStyles,
+}
+
+impl Display for CarpelFusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CarpelFusion::Apocarpous => write!(f, "apocarpous"),
+            CarpelFusion::Syncarpous => write!(f, "syncarpous"),
+            CarpelFusion::FreeStyles => write!(f, "styles free"),
+            CarpelFusion::UnitedStyles => write!(f, "styles united"),
+        }
+    }
+}
+
+impl FromStr for CarpelFusion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "apocarpous" => Ok(Self::Apocarpous),
+            "s" | "syncarpous" => Ok(Self::Syncarpous),
+            "fs" | "styles free" | "free styles" => Ok(Self::FreeStyles),
+            "us" | "styles united" | "united styles" => Ok(Self::UnitedStyles),
+            cf_str => Err(Error::new(ErrorKind::FromStr(format!(
+                "the string: {}, does not correspond to a carpel fusion",
+                cf_str
+            )))),
+        }
+    }
+}
+
+impl CarpelFusion {
+    /// Whether the ovaries are fused, which is what the
+    /// connation brackets show in the formula, e.g. G(5)
+    pub fn ovaries_fused(&self) -> bool {
+        matches!(self, CarpelFusion::Syncarpous | CarpelFusion::FreeStyles)
+    }
+}
+
+/// Diagnostic arrangements of the stamens, in which the
+/// stamens differ in length, or are grouped or fused
+/// in a particular way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StamenArrangement {
+    /// Four stamens, in two pairs of unequal length
+    Didynamous,
+    /// Six stamens, four long and two short
+    Tetradynamous,
+    /// Filaments fused into a single tube
+    Monadelphous,
+    /// Filaments fused into two groups, usually 9+1
+    Diadelphous,
+    /// Filaments fused into several bundles
+    Polyadelphous,
+    /// Anthers fused into a tube, filaments free
+    Syngenesious,
+    /// Stamens borne on the petals
+    Epipetalous,
+}
+
+impl Display for StamenArrangement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StamenArrangement::Didynamous => write!(f, "didynamous"),
+            StamenArrangement::Tetradynamous => write!(f, "tetradynamous"),
+            StamenArrangement::Monadelphous => write!(f, "monadelphous"),
+            StamenArrangement::Diadelphous => write!(f, "diadelphous"),
+            StamenArrangement::Polyadelphous => write!(f, "polyadelphous"),
+            StamenArrangement::Syngenesious => write!(f, "syngenesious"),
+            StamenArrangement::Epipetalous => write!(f, "epipetalous"),
+        }
+    }
+}
+
+impl FromStr for StamenArrangement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dd" | "didynamous" => Ok(Self::Didynamous),
+            "td" | "tetradynamous" => Ok(Self::Tetradynamous),
+            "mo" | "monadelphous" => Ok(Self::Monadelphous),
+            "di" | "diadelphous" => Ok(Self::Diadelphous),
+            "po" | "polyadelphous" => Ok(Self::Polyadelphous),
+            "sy" | "syngenesious" => Ok(Self::Syngenesious),
+            "ep" | "epipetalous" => Ok(Self::Epipetalous),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "stamen arrangement: {}, not recognised",
+                other
+            )))),
+        }
+    }
+}
+
+/// The position of the (outer) stamen whorl relative
+/// to the petals, or the inner perianth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StamenPosition {
+    /// Alternating with the petals (opposite the sepals)
+    Alternate,
+    /// Opposite the petals
+    Opposite,
+}
+
+impl Display for StamenPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StamenPosition::Alternate => write!(f, "alternipetalous"),
+            StamenPosition::Opposite => write!(f, "antepetalous"),
+        }
+    }
+}
+
+impl FromStr for StamenPosition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alt" | "alternipetalous" => Ok(Self::Alternate),
+            "opp" | "antepetalous" => Ok(Self::Opposite),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "stamen position: {}, not recognised",
+                other
+            )))),
+        }
+    }
+}
+
+/// The organs within a stamen or carpel which may be
+/// connate, where connation of the whole part is not
+/// specific enough.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnateOrgan {
+    /// The stalks of the stamens
+    Filaments,
+    /// The pollen bearing heads of the stamens
+    Anthers,
+    /// The styles of the carpels
+    Styles,
+    /// The stigmas of the carpels
+    Stigmas,
+}
+
+impl Display for ConnateOrgan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnateOrgan::Filaments => write!(f, "filaments"),
+            ConnateOrgan::Anthers => write!(f, "anthers"),
+            ConnateOrgan::Styles => write!(f, "styles"),
+            ConnateOrgan::Stigmas => write!(f, "stigmas"),
+        }
+    }
+}
+
+impl FromStr for ConnateOrgan {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cf" | "filaments" => Ok(Self::Filaments),
+            "ca" | "anthers" => Ok(Self::Anthers),
+            "cst" | "styles" => Ok(Self::Styles),
+            "csg" | "stigmas" => Ok(Self::Stigmas),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "connate organ: {}, not recognised",
+                other
+            )))),
+        }
+    }
+}
+
+impl ConnateOrgan {
+    /// The floral part which this organ belongs to
+    pub fn part(&self) -> Part {
+        match self {
+            ConnateOrgan::Filaments | ConnateOrgan::Anthers => Part::Stamens,
+            ConnateOrgan::Styles | ConnateOrgan::Stigmas => Part::Carpels,
+        }
+    }
+}
+
+/// A count of something in a floral part which is not
+/// a whorl in itself, such as the locules of an ovary
+/// or the ovules in a locule.
+#[derive(Debug, Clone)]
+pub enum PartCount {
+    /// A single number
+    Number(FloralPartNumber),
+    /// A range, from min to max
+    Range(FloralPartNumber, FloralPartNumber),
+}
+
+impl FromStr for PartCount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('-') {
+            Some((min, max)) => Ok(Self::Range(
+                FloralPartNumber::from_str(min)?,
+                FloralPartNumber::from_str(max)?,
+            )),
+            None => Ok(Self::Number(FloralPartNumber::from_str(s)?)),
+        }
+    }
+}
+
+impl Display for PartCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartCount::Number(n) => write!(f, "{}", n),
+            PartCount::Range(min, max) => write!(f, "{}-{}", min, max),
+        }
+    }
+}
+
 /// The part of the flower, which
 /// occurs as a whorl.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Part {
+    /// An epicalyx, a whorl of bracts outside the calyx
+    Epicalyx,
+    /// Bracteoles, small bracts subtending the flower
+    Bracteoles,
     Tepals,
     Calyx,
     Petals,
@@ -707,6 +1255,8 @@ impl FromStr for Part {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "EpK" => Ok(Self::Epicalyx),
+            "Br" => Ok(Self::Bracteoles),
             "T" => Ok(Self::Tepals),
             "K" => Ok(Self::Calyx),
             "C" => Ok(Self::Petals),
@@ -723,6 +1273,8 @@ impl FromStr for Part {
 impl Display for Part {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Part::Epicalyx => write!(f, "EpK"),
+            Part::Bracteoles => write!(f, "Br"),
             Part::Tepals => write!(f, "T"),
             Part::Calyx => write!(f, "K"),
             Part::Petals => write!(f, "C"),
@@ -748,32 +1300,155 @@ impl Display for Sterile {
     }
 }
 
+/// Accessory floral structures which are not one of
+/// the main floral parts.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Accessory {
+    /// A nectar disc, between the stamens and the ovary
+    Disc,
+    /// A corona, between the perianth and the stamens
+    Corona,
+    /// Nectaries on the perianth
+    Nectaries,
+    /// Nectaries in the septa of the ovary
+    SeptalNectaries,
+    /// Staminodes modified into nectaries
+    StaminodialNectaries,
+    /// A nectar spur on the perianth
+    Spur,
+}
+
+impl Display for Accessory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Accessory::Disc => write!(f, "disc"),
+            Accessory::Corona => write!(f, "corona"),
+            Accessory::Nectaries => write!(f, "nectaries"),
+            Accessory::SeptalNectaries => write!(f, "septal nectaries"),
+            Accessory::StaminodialNectaries => write!(f, "staminodial nectaries"),
+            Accessory::Spur => write!(f, "spur"),
+        }
+    }
+}
+
+impl FromStr for Accessory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "d" | "disc" => Ok(Self::Disc),
+            "co" | "corona" => Ok(Self::Corona),
+            "n" | "nectaries" => Ok(Self::Nectaries),
+            "sep" | "septal nectaries" => Ok(Self::SeptalNectaries),
+            "sn" | "staminodial nectaries" => Ok(Self::StaminodialNectaries),
+            "sp" | "spur" => Ok(Self::Spur),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "accessory structure: {}, not recognised",
+                other
+            )))),
+        }
+    }
+}
+
+/// The inflorescence, or the arrangement of the flowers
+/// on the plant. A floral formula describes a single
+/// flower, so this is kept alongside.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Inflorescence {
+    Capitulum,
+    Catkin,
+    Corymb,
+    Cyathium,
+    Cyme,
+    Panicle,
+    Raceme,
+    Solitary,
+    Spadix,
+    Spike,
+    Spikelet,
+    Thyrse,
+    Umbel,
+}
+
+impl Display for Inflorescence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Inflorescence::Capitulum => write!(f, "capitulum"),
+            Inflorescence::Catkin => write!(f, "catkin"),
+            Inflorescence::Corymb => write!(f, "corymb"),
+            Inflorescence::Cyathium => write!(f, "cyathium"),
+            Inflorescence::Cyme => write!(f, "cyme"),
+            Inflorescence::Panicle => write!(f, "panicle"),
+            Inflorescence::Raceme => write!(f, "raceme"),
+            Inflorescence::Solitary => write!(f, "solitary"),
+            Inflorescence::Spadix => write!(f, "spadix"),
+            Inflorescence::Spike => write!(f, "spike"),
+            Inflorescence::Spikelet => write!(f, "spikelet"),
+            Inflorescence::Thyrse => write!(f, "thyrse"),
+            Inflorescence::Umbel => write!(f, "umbel"),
+        }
+    }
+}
+
+impl FromStr for Inflorescence {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "capitulum" | "head" => Ok(Self::Capitulum),
+            "catkin" => Ok(Self::Catkin),
+            "corymb" => Ok(Self::Corymb),
+            "cyathium" => Ok(Self::Cyathium),
+            "cyme" => Ok(Self::Cyme),
+            "panicle" => Ok(Self::Panicle),
+            "raceme" => Ok(Self::Raceme),
+            "solitary" => Ok(Self::Solitary),
+            "spadix" => Ok(Self::Spadix),
+            "spike" => Ok(Self::Spike),
+            "spikelet" => Ok(Self::Spikelet),
+            "thyrse" => Ok(Self::Thyrse),
+            "umbel" => Ok(Self::Umbel),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "inflorescence: {}, not recognised",
+                other
+            )))),
+        }
+    }
+}
+
 /// All the different fruit types. A growing list.
-#[derive(Debug, Copy, Clone)]
+/// Each named type is defined in terms of its
+/// [`FruitAttributes`].
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Fruit {
     Achene,
     Berry,
     Berrylets,
     Capsule, // note there are many different capsule types
     Caryopsis,
+    Cypsela,
     DDrupe, // dehiscent drupe
     Drupe,
     Drupelets,
     Follicle,
+    Hesperidium,
     IPod, // indehiscent pod
     Legume,
     Loment,
+    Mericarp,
     Nut,
     AggregateOfNuts,
+    Pepo,
     Pome,
     Samara,
     Schizocarp,
     Silique,
+    Strobile,
+    Syconium,
     Utricle,
     None,
 }
 
-// TODO: the fruit collection needs to be bigger, e.g. drupelets, berrylets...
 impl Display for Fruit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -782,19 +1457,25 @@ impl Display for Fruit {
             Fruit::Berrylets => write!(f, "berrylets"),
             Fruit::Capsule => write!(f, "capsule"),
             Fruit::Caryopsis => write!(f, "caryopsis"),
+            Fruit::Cypsela => write!(f, "cypsela"),
             Fruit::DDrupe => write!(f, "dehiscent drupe"),
             Fruit::Drupe => write!(f, "drupe"),
             Fruit::Drupelets => write!(f, "drupelets"),
             Fruit::Follicle => write!(f, "follicle"),
+            Fruit::Hesperidium => write!(f, "hesperidium"),
             Fruit::IPod => write!(f, "indehiscent pod"),
             Fruit::Legume => write!(f, "legume"),
             Fruit::Loment => write!(f, "loment"),
+            Fruit::Mericarp => write!(f, "mericarp"),
             Fruit::Nut => write!(f, "nut"),
             Fruit::AggregateOfNuts => write!(f, "aggregate of nuts"),
+            Fruit::Pepo => write!(f, "pepo"),
             Fruit::Pome => write!(f, "pome"),
             Fruit::Samara => write!(f, "samara"),
             Fruit::Schizocarp => write!(f, "schizocarp"),
             Fruit::Silique => write!(f, "silique"),
+            Fruit::Strobile => write!(f, "strobile"),
+            Fruit::Syconium => write!(f, "syconium"),
             Fruit::Utricle => write!(f, "utricle"),
             Fruit::None => write!(f, "no fruit"),
         }
@@ -809,23 +1490,29 @@ impl FromStr for Fruit {
             "achene" | "achenes" => Ok(Self::Achene),
             "berry" | "berries" => Ok(Self::Berry),
             "berrylets" => Ok(Self::Berrylets),
-            "capsule" | "fleshy capsule" => Ok(Self::Capsule),
+            "capsule" | "capsules" => Ok(Self::Capsule),
             "caryopsis" => Ok(Self::Caryopsis),
+            "cypsela" | "cypselae" => Ok(Self::Cypsela),
             "dehiscent drupe" => Ok(Self::DDrupe),
             "drupe" | "drupes" => Ok(Self::Drupe),
             "drupelets" => Ok(Self::Drupelets),
             "follicle" | "follicles" => Ok(Self::Follicle),
+            "hesperidium" => Ok(Self::Hesperidium),
             "indehiscent pod" => Ok(Self::IPod),
             "legume" => Ok(Self::Legume),
             "loment" => Ok(Self::Loment),
+            "mericarp" | "mericarps" => Ok(Self::Mericarp),
             "nut" => Ok(Self::Nut),
             "aggregate of nuts" => Ok(Self::AggregateOfNuts),
+            "pepo" => Ok(Self::Pepo),
             "pome" => Ok(Self::Pome),
             "samara" | "samaras" => Ok(Self::Samara),
             "schizocarp" => Ok(Self::Schizocarp),
             "silique" => Ok(Self::Silique),
+            "strobile" => Ok(Self::Strobile),
+            "syconium" => Ok(Self::Syconium),
             "utricle" => Ok(Self::Utricle),
-            "-" | "" => Ok(Self::None),
+            "-" | "" | "no fruit" => Ok(Self::None),
             other => Err(Error::new(ErrorKind::FromStr(format!(
                 "fruit: {}, not recognised",
                 other
@@ -834,40 +1521,524 @@ impl FromStr for Fruit {
     }
 }
 
-/// An individual floral part
-#[derive(Debug, Clone)]
-pub struct FloralPart {
-    /// Either the calyx, petals, stamens
-    /// or carpels
-    part: Part,
-    /// Is the floral part connate?
-    connate: bool,
-    /// Is there variation in connation?
-    connation_variation: bool,
-    /// All the whorls in this floral part which are
-    /// differentiated
-    whorls: Vec<Whorl>,
-    /// Ovary information makes most sense here
-    ovary: Option<Ovary>,
-}
+impl Fruit {
+    /// The attributes which define each named fruit type.
+    /// [`Fruit::None`] has no attributes.
+    pub fn attributes(&self) -> Option<FruitAttributes> {
+        use Dehiscence::*;
+        use FruitOrigin::*;
+        use Pericarp::*;
+
+        let one = PartCount::Number(FloralPartNumber::Finite(1));
+        let range =
+            |min: u32, max: FloralPartNumber| PartCount::Range(FloralPartNumber::Finite(min), max);
+        let inf = FloralPartNumber::Infinite;
+
+        let (pericarp, dehiscence, origin, seeds) = match self {
+            Fruit::Achene => (Dry, Indehiscent, Simple, one),
+            Fruit::Berry => (Fleshy, Indehiscent, Simple, range(1, inf)),
+            Fruit::Berrylets => (Fleshy, Indehiscent, Aggregate, range(1, inf)),
+            Fruit::Capsule => (Dry, Dehiscent, Simple, range(2, inf)),
+            Fruit::Caryopsis => (Dry, Indehiscent, Simple, one),
+            Fruit::Cypsela => (Dry, Indehiscent, Simple, one),
+            Fruit::DDrupe => (Fleshy, Dehiscent, Simple, range(1, inf)),
+            Fruit::Drupe => (Fleshy, Indehiscent, Simple, range(1, inf)),
+            Fruit::Drupelets => (Fleshy, Indehiscent, Aggregate, range(1, inf)),
+            Fruit::Follicle => (Variable, Dehiscent, Simple, range(1, inf)),
+            Fruit::Hesperidium => (Fleshy, Indehiscent, Simple, range(1, inf)),
+            Fruit::IPod => (Dry, Indehiscent, Simple, range(1, inf)),
+            Fruit::Legume => (Dry, Dehiscent, Simple, range(1, inf)),
+            Fruit::Loment => (Dry, Schizocarpic, Simple, range(1, inf)),
+            Fruit::Mericarp => (
+                Dry,
+                Indehiscent,
+                Simple,
+                range(1, FloralPartNumber::Finite(2)),
+            ),
+            Fruit::Nut => (Dry, Indehiscent, Simple, one),
+            Fruit::AggregateOfNuts => (Dry, Indehiscent, Aggregate, one),
+            Fruit::Pepo => (Fleshy, Indehiscent, Simple, range(1, inf)),
+            Fruit::Pome => (Fleshy, Indehiscent, Simple, range(1, inf)),
+            Fruit::Samara => (
+                Dry,
+                Indehiscent,
+                Simple,
+                range(1, FloralPartNumber::Finite(2)),
+            ),
+            Fruit::Schizocarp => (Variable, Schizocarpic, Simple, range(2, inf)),
+            Fruit::Silique => (Dry, Dehiscent, Simple, range(1, inf)),
+            Fruit::Strobile => (Dry, Indehiscent, Multiple, range(1, inf)),
+            Fruit::Syconium => (Fleshy, Indehiscent, Multiple, range(1, inf)),
+            Fruit::Utricle => (Dry, Indehiscent, Simple, one),
+            Fruit::None => return None,
+        };
 
-impl FloralPart {
-    /// Set the floral part
-    pub fn set_part(&mut self, part: Part) {
-        self.part = part;
-    }
-    /// Set the connation of the floral part
-    pub fn set_connation(&mut self, connation: bool) {
-        self.connate = connation;
+        Some(FruitAttributes {
+            pericarp,
+            dehiscence,
+            origin,
+            seeds,
+        })
     }
-    /// Set the connation variation of the floral part
+}
+
+/// Whether the fruit wall (pericarp) is dry or fleshy
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Pericarp {
+    Dry,
+    Fleshy,
+    /// Dry to fleshy, depending on the species
+    Variable,
+}
+
+impl Display for Pericarp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pericarp::Dry => write!(f, "dry"),
+            Pericarp::Fleshy => write!(f, "fleshy"),
+            Pericarp::Variable => write!(f, "dry to fleshy"),
+        }
+    }
+}
+
+/// Whether the fruit opens to release the seeds
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Dehiscence {
+    Dehiscent,
+    Indehiscent,
+    /// Breaking apart into one seeded units
+    Schizocarpic,
+}
+
+impl Display for Dehiscence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dehiscence::Dehiscent => write!(f, "dehiscent"),
+            Dehiscence::Indehiscent => write!(f, "indehiscent"),
+            Dehiscence::Schizocarpic => write!(f, "schizocarpic"),
+        }
+    }
+}
+
+/// How many flowers and carpels a fruit develops from
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FruitOrigin {
+    /// From a single ovary of one flower
+    Simple,
+    /// From the separate carpels of one flower
+    Aggregate,
+    /// From the ovaries of many flowers
+    Multiple,
+}
+
+impl Display for FruitOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FruitOrigin::Simple => write!(f, "simple"),
+            FruitOrigin::Aggregate => write!(f, "aggregate"),
+            FruitOrigin::Multiple => write!(f, "multiple"),
+        }
+    }
+}
+
+/// The attributes which describe a fruit.
+#[derive(Debug, Clone)]
+pub struct FruitAttributes {
+    /// Dry or fleshy
+    pericarp: Pericarp,
+    /// Dehiscent or not
+    dehiscence: Dehiscence,
+    /// Simple, aggregate or multiple
+    origin: FruitOrigin,
+    /// The number of seeds
+    seeds: PartCount,
+}
+
+impl FruitAttributes {
+    /// Set the pericarp
+    pub fn set_pericarp(&mut self, pericarp: Pericarp) {
+        self.pericarp = pericarp;
+    }
+    /// Get the pericarp
+    pub fn get_pericarp(&self) -> Pericarp {
+        self.pericarp
+    }
+    /// Get the dehiscence
+    pub fn get_dehiscence(&self) -> Dehiscence {
+        self.dehiscence
+    }
+    /// Get the origin
+    pub fn get_origin(&self) -> FruitOrigin {
+        self.origin
+    }
+    /// Get the number of seeds
+    pub fn get_seeds(&self) -> &PartCount {
+        &self.seeds
+    }
+}
+
+impl Display for FruitAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}, {} seeded",
+            self.pericarp, self.dehiscence, self.origin, self.seeds
+        )
+    }
+}
+
+/// A fruit in a formula: the named type, along with its
+/// attributes. These are the attributes of the named type,
+/// unless the database says otherwise (e.g. a fleshy capsule).
+#[derive(Debug, Clone)]
+pub struct FruitDescription {
+    /// The named fruit type
+    fruit: Fruit,
+    /// The attributes of this fruit
+    attributes: Option<FruitAttributes>,
+}
+
+impl FruitDescription {
+    /// Get the named fruit type
+    pub fn get_fruit(&self) -> Fruit {
+        self.fruit
+    }
+    /// Get the attributes
+    pub fn get_attributes(&self) -> &Option<FruitAttributes> {
+        &self.attributes
+    }
+    /// Whether the pericarp differs from the named type
+    pub fn is_modified(&self) -> bool {
+        match (&self.attributes, self.fruit.attributes()) {
+            (Some(a), Some(b)) => a.pericarp != b.pericarp,
+            _ => false,
+        }
+    }
+}
+
+impl From<Fruit> for FruitDescription {
+    fn from(fruit: Fruit) -> Self {
+        Self {
+            fruit,
+            attributes: fruit.attributes(),
+        }
+    }
+}
+
+impl Display for FruitDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.attributes, self.is_modified()) {
+            (Some(a), true) => write!(f, "{} {}", a.pericarp, self.fruit),
+            _ => write!(f, "{}", self.fruit),
+        }
+    }
+}
+
+impl FromStr for FruitDescription {
+    type Err = Error;
+
+    // e.g. "capsule", or "fleshy capsule"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(fruit) = Fruit::from_str(s) {
+            return Ok(fruit.into());
+        }
+        let (modifier, name) = s.split_once(' ').unwrap_or(("", s));
+        let pericarp = match modifier {
+            "dry" => Pericarp::Dry,
+            "fleshy" => Pericarp::Fleshy,
+            _ => {
+                return Err(Error::new(ErrorKind::FromStr(format!(
+                    "fruit: {}, not recognised",
+                    s
+                ))))
+            }
+        };
+        let mut description = FruitDescription::from(Fruit::from_str(name)?);
+        if let Some(a) = description.attributes.as_mut() {
+            a.set_pericarp(pericarp);
+        }
+        Ok(description)
+    }
+}
+
+/// A query on fruits by their attributes and/or name,
+/// e.g. "fleshy indehiscent" or "dry capsule".
+#[derive(Debug, Clone, Default)]
+pub struct FruitQuery {
+    pericarp: Option<Pericarp>,
+    dehiscence: Option<Dehiscence>,
+    origin: Option<FruitOrigin>,
+    fruit: Option<Fruit>,
+}
+
+impl FruitQuery {
+    /// Whether a fruit matches every part of the query. A
+    /// pericarp which is dry to fleshy matches either.
+    pub fn matches(&self, description: &FruitDescription) -> bool {
+        let attributes = match description.get_attributes() {
+            Some(a) => a,
+            None => return false,
+        };
+        let pericarp = match (self.pericarp, attributes.pericarp) {
+            (None, _) | (Some(_), Pericarp::Variable) => true,
+            (Some(q), p) => q == p,
+        };
+        let dehiscence = self.dehiscence.map_or(true, |d| d == attributes.dehiscence);
+        let origin = self.origin.map_or(true, |o| o == attributes.origin);
+        let fruit = self.fruit.map_or(true, |f| f == description.fruit);
+
+        pericarp && dehiscence && origin && fruit
+    }
+}
+
+impl FromStr for FruitQuery {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // a plain fruit name, which may have spaces in it
+        if let Ok(fruit) = Fruit::from_str(s) {
+            return Ok(Self {
+                fruit: Some(fruit),
+                ..Default::default()
+            });
+        }
+        let mut query = FruitQuery::default();
+        let mut name = Vec::new();
+        for word in s.split_whitespace() {
+            match word {
+                "dry" => query.pericarp = Some(Pericarp::Dry),
+                "fleshy" => query.pericarp = Some(Pericarp::Fleshy),
+                "dehiscent" => query.dehiscence = Some(Dehiscence::Dehiscent),
+                "indehiscent" => query.dehiscence = Some(Dehiscence::Indehiscent),
+                "schizocarpic" => query.dehiscence = Some(Dehiscence::Schizocarpic),
+                "simple" => query.origin = Some(FruitOrigin::Simple),
+                "aggregate" => query.origin = Some(FruitOrigin::Aggregate),
+                "multiple" => query.origin = Some(FruitOrigin::Multiple),
+                "fruit" | "fruits" => (),
+                other => name.push(other),
+            }
+        }
+        if !name.is_empty() {
+            query.fruit = Some(Fruit::from_str(&name.join(" "))?);
+        }
+        Ok(query)
+    }
+}
+
+/// How much endosperm, the nutritive tissue around
+/// the embryo, is left in the mature seed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Endosperm {
+    /// Endosperm makes up much of the seed
+    Copious,
+    /// A thin layer of endosperm is left
+    Scant,
+    /// Food is stored in the embryo itself
+    Absent,
+}
+
+impl Display for Endosperm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endosperm::Copious => write!(f, "endosperm"),
+            Endosperm::Scant => write!(f, "scant endosperm"),
+            Endosperm::Absent => write!(f, "no endosperm"),
+        }
+    }
+}
+
+/// The seed and embryo characters, which are kept alongside
+/// the fruit.
+#[derive(Debug, Clone, Default)]
+pub struct Seed {
+    /// The endosperm in the mature seed
+    endosperm: Option<Endosperm>,
+    /// Is there an aril?
+    aril: bool,
+    /// Is the seed winged?
+    winged: bool,
+    /// The number of cotyledons in the embryo
+    cotyledons: Option<PartCount>,
+}
+
+impl Seed {
+    /// Get the endosperm
+    pub fn get_endosperm(&self) -> Option<Endosperm> {
+        self.endosperm
+    }
+    /// Get whether there is an aril
+    pub fn get_aril(&self) -> bool {
+        self.aril
+    }
+    /// Get whether the seed is winged
+    pub fn get_winged(&self) -> bool {
+        self.winged
+    }
+    /// Get the number of cotyledons
+    pub fn get_cotyledons(&self) -> &Option<PartCount> {
+        &self.cotyledons
+    }
+}
+
+impl Display for Seed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut characters = Vec::new();
+        if let Some(endosperm) = self.endosperm {
+            characters.push(endosperm.to_string());
+        }
+        if self.aril {
+            characters.push("arillate".to_string());
+        }
+        if self.winged {
+            characters.push("winged".to_string());
+        }
+        if let Some(cotyledons) = &self.cotyledons {
+            characters.push(format!("{} cot", cotyledons));
+        }
+        write!(f, "{}", characters.join(", "))
+    }
+}
+
+impl FromStr for Seed {
+    type Err = Error;
+
+    // the codes, e.g. "e;ar;c2", or what is displayed,
+    // e.g. "endosperm, arillate, 2 cot"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut seed = Seed::default();
+        for character in s.split([';', ',']).map(|e| e.trim()) {
+            match character {
+                "e" | "endosperm" => seed.endosperm = Some(Endosperm::Copious),
+                "se" | "scant endosperm" => seed.endosperm = Some(Endosperm::Scant),
+                "ne" | "no endosperm" => seed.endosperm = Some(Endosperm::Absent),
+                "ar" | "arillate" => seed.aril = true,
+                "w" | "winged" => seed.winged = true,
+                other => {
+                    let cotyledons = other
+                        .strip_prefix('c')
+                        .or_else(|| other.strip_suffix(" cot"))
+                        // a number of cotyledons has to be given
+                        .filter(|e| !e.trim().is_empty())
+                        .ok_or_else(|| {
+                            Error::new(ErrorKind::FromStr(format!(
+                                "seed character: {}, not recognised",
+                                other
+                            )))
+                        })?;
+                    seed.cotyledons = Some(PartCount::from_str(cotyledons)?);
+                }
+            }
+        }
+        Ok(seed)
+    }
+}
+
+/// An individual floral part
+#[derive(Debug, Clone)]
+pub struct FloralPart {
+    /// Either the calyx, petals, stamens
+    /// or carpels
+    part: Part,
+    /// Is the floral part connate?
+    connate: bool,
+    /// Is there variation in connation?
+    connation_variation: bool,
+    /// Which organs are connate, for stamens (filaments
+    /// or anthers) and carpels (styles or stigmas)
+    connate_organs: Vec<ConnateOrgan>,
+    /// All the whorls in this floral part which are
+    /// differentiated
+    whorls: Vec<Whorl>,
+    /// Ovary information makes most sense here
+    ovary: Vec<Ovary>,
+    /// Whether a hypanthium (floral cup) is present,
+    /// only for the carpels
+    hypanthium: bool,
+    /// Placentation(s), only for the carpels
+    placentation: Vec<Placentation>,
+    /// The number of locules, only for the carpels
+    locules: Option<PartCount>,
+    /// The number of ovules per locule, only for
+    /// the carpels
+    ovules: Option<PartCount>,
+    /// How the carpels are fused, only for the carpels
+    carpel_fusion: Option<CarpelFusion>,
+    /// The number of stigma lobes, only for the carpels
+    stigmas: Option<PartCount>,
+    /// Arrangement(s) of the stamens, only for the stamens
+    stamen_arrangement: Vec<StamenArrangement>,
+    /// Position of the stamens relative to the petals, only
+    /// for the stamens
+    stamen_position: Option<StamenPosition>,
+}
+
+impl FloralPart {
+    /// Set the floral part
+    pub fn set_part(&mut self, part: Part) {
+        self.part = part;
+    }
+    /// Set the connation of the floral part
+    pub fn set_connation(&mut self, connation: bool) {
+        self.connate = connation;
+    }
+    /// Set the connation variation of the floral part
     pub fn set_connation_variation(&mut self, connation_variation: bool) {
         self.connation_variation = connation_variation;
     }
-    /// Set the status of the ovary for the floral part
-    pub fn set_ovary(&mut self, ovary: Option<Ovary>) {
+    /// Add an organ which is connate. The stamens are connate
+    /// if either the filaments or the anthers are, but the connation
+    /// of the carpels follows the ovaries, not the styles or stigmas.
+    pub fn add_connate_organ(&mut self, organ: ConnateOrgan) {
+        if organ.part() == Part::Stamens {
+            self.connate = true;
+        }
+        if !self.connate_organs.contains(&organ) {
+            self.connate_organs.push(organ);
+        }
+    }
+    /// Set the positions of the ovary for the floral part, more
+    /// than one where the position varies across the group
+    pub fn set_ovary(&mut self, mut ovary: Vec<Ovary>) {
+        ovary.sort();
+        ovary.dedup();
         self.ovary = ovary;
     }
+    /// Set whether there is a hypanthium
+    pub fn set_hypanthium(&mut self, hypanthium: bool) {
+        self.hypanthium = hypanthium;
+    }
+    /// Set the placentation(s) of the carpels
+    pub fn set_placentation(&mut self, placentation: Vec<Placentation>) {
+        self.placentation = placentation;
+    }
+    /// Set the number of locules in the ovary
+    pub fn set_locules(&mut self, locules: Option<PartCount>) {
+        self.locules = locules;
+    }
+    /// Set the number of ovules per locule
+    pub fn set_ovules(&mut self, ovules: Option<PartCount>) {
+        self.ovules = ovules;
+    }
+    /// Set the carpel fusion. This also sets the connation
+    /// of the floral part, as the ovaries may or may not be fused.
+    pub fn set_carpel_fusion(&mut self, carpel_fusion: Option<CarpelFusion>) {
+        if let Some(cf) = carpel_fusion {
+            self.connate = cf.ovaries_fused();
+        }
+        self.carpel_fusion = carpel_fusion;
+    }
+    /// Set the number of stigma lobes
+    pub fn set_stigmas(&mut self, stigmas: Option<PartCount>) {
+        self.stigmas = stigmas;
+    }
+    /// Set the arrangement(s) of the stamens
+    pub fn set_stamen_arrangement(&mut self, stamen_arrangement: Vec<StamenArrangement>) {
+        self.stamen_arrangement = stamen_arrangement;
+    }
+    /// Set the position of the stamens relative to the petals
+    pub fn set_stamen_position(&mut self, stamen_position: Option<StamenPosition>) {
+        self.stamen_position = stamen_position;
+    }
     /// Get the floral part in question
     pub fn get_part(&self) -> Part {
         self.part.clone()
@@ -880,13 +2051,97 @@ impl FloralPart {
     pub fn get_connation_variation(&self) -> bool {
         self.connation_variation
     }
+    /// Get the organs which are connate
+    pub fn get_connate_organs(&self) -> &Vec<ConnateOrgan> {
+        &self.connate_organs
+    }
+    /// The connate organs which the stamen arrangement doesn't
+    /// already say are connate, as syngenesious does the anthers
+    pub fn connate_organs_to_show(&self) -> Vec<ConnateOrgan> {
+        let syngenesious = self
+            .stamen_arrangement
+            .contains(&StamenArrangement::Syngenesious);
+        self.connate_organs
+            .iter()
+            .copied()
+            .filter(|e| !(syngenesious && *e == ConnateOrgan::Anthers))
+            .collect()
+    }
     /// Get the value of the whorls
     pub fn get_whorls(&self) -> Vec<Whorl> {
         self.whorls.clone()
     }
-    /// Get the value of the ovary
-    pub fn get_ovary(&self) -> Option<Ovary> {
-        self.ovary
+    /// Get the positions of the ovary
+    pub fn get_ovary(&self) -> &Vec<Ovary> {
+        &self.ovary
+    }
+    /// Get whether there is a hypanthium
+    pub fn get_hypanthium(&self) -> bool {
+        self.hypanthium
+    }
+    /// Get the placentation(s)
+    pub fn get_placentation(&self) -> &Vec<Placentation> {
+        &self.placentation
+    }
+    /// Get the number of locules
+    pub fn get_locules(&self) -> &Option<PartCount> {
+        &self.locules
+    }
+    /// Get the number of ovules per locule
+    pub fn get_ovules(&self) -> &Option<PartCount> {
+        &self.ovules
+    }
+    /// Get the carpel fusion
+    pub fn get_carpel_fusion(&self) -> Option<CarpelFusion> {
+        self.carpel_fusion
+    }
+    /// Get the number of stigma lobes
+    pub fn get_stigmas(&self) -> &Option<PartCount> {
+        &self.stigmas
+    }
+    /// Get the arrangement(s) of the stamens
+    pub fn get_stamen_arrangement(&self) -> &Vec<StamenArrangement> {
+        &self.stamen_arrangement
+    }
+    /// Get the position of the stamens relative to the petals
+    pub fn get_stamen_position(&self) -> Option<StamenPosition> {
+        self.stamen_position
+    }
+}
+
+/// The position of a whorl in a floral part with more
+/// than one whorl, e.g. the outer and inner tepals in T3+3.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhorlPosition {
+    Outer,
+    Middle,
+    Inner,
+}
+
+impl Display for WhorlPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WhorlPosition::Outer => write!(f, "outer"),
+            WhorlPosition::Middle => write!(f, "middle"),
+            WhorlPosition::Inner => write!(f, "inner"),
+        }
+    }
+}
+
+/// Whether the parts of a (perianth) whorl look like
+/// petals or sepals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhorlNature {
+    Petaloid,
+    Sepaloid,
+}
+
+impl Display for WhorlNature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WhorlNature::Petaloid => write!(f, "petaloid"),
+            WhorlNature::Sepaloid => write!(f, "sepaloid"),
+        }
     }
 }
 
@@ -911,6 +2166,10 @@ pub struct Whorl {
     connation: bool,
     /// Connation variation
     connation_variation: bool,
+    /// Outer or inner, if there is more than one whorl
+    position: Option<WhorlPosition>,
+    /// Petaloid or sepaloid
+    nature: Option<WhorlNature>,
 }
 
 impl Whorl {
@@ -935,8 +2194,23 @@ impl Whorl {
             sterile,
             connation,
             connation_variation,
+            position: None,
+            nature: None,
         }
     }
+    /// Set the position of the whorl
+    pub fn set_position(&mut self, position: Option<WhorlPosition>) {
+        self.position = position;
+    }
+    /// Set the nature of the whorl
+    pub fn set_nature(&mut self, nature: Option<WhorlNature>) {
+        self.nature = nature;
+    }
+    /// Set the connation of the whorl
+    pub fn set_connation(&mut self, connation: bool, connation_variation: bool) {
+        self.connation = connation;
+        self.connation_variation = connation && connation_variation;
+    }
     /// Get the number
     pub fn get_number(&self) -> &Option<FloralPartNumber> {
         &self.number
@@ -961,6 +2235,14 @@ impl Whorl {
     pub fn get_connation_variation(&self) -> bool {
         self.connation_variation
     }
+    /// Get the position
+    pub fn get_position(&self) -> Option<WhorlPosition> {
+        self.position
+    }
+    /// Get the nature
+    pub fn get_nature(&self) -> Option<WhorlNature> {
+        self.nature
+    }
 }
 
 // TODO: impl connation and connation variation here.
@@ -1002,8 +2284,17 @@ impl Default for FloralPart {
             part: Part::Tepals,
             connate: false,
             connation_variation: false,
+            connate_organs: vec![],
             whorls: vec![],
-            ovary: None,
+            ovary: Vec::new(),
+            hypanthium: false,
+            placentation: vec![],
+            locules: None,
+            ovules: None,
+            carpel_fusion: None,
+            stigmas: None,
+            stamen_arrangement: vec![],
+            stamen_position: None,
         }
     }
 }
@@ -1017,16 +2308,10 @@ impl Display for FloralPart {
             whorl_strings.push(whorl.to_string());
         }
 
-        // TODO: HERE IS WHERE THE OVARY POSITION GOES
-        let part = if let Some(ovary) = self.ovary {
-            match ovary {
-                Ovary::Superior => format!("{}{}", self.part, '\u{0332}'),
-                Ovary::Inferior => format!("{}{}", self.part, '\u{305}'),
-                Ovary::Both => format!("{}{}{}", self.part, '\u{305}', '\u{0332}'),
-            }
-        } else {
-            self.part.to_string()
-        };
+        // the ovary position is marked on the G, with every mark
+        // where the position varies
+        let mut part = self.part.to_string();
+        part.extend(self.ovary.iter().map(|e| e.mark()));
 
         // connation is () around the floral part.
         // variation is denoted as (].
@@ -1034,6 +2319,70 @@ impl Display for FloralPart {
             (true, true) => write!(f, "({}{}]", part, whorl_strings.join("+")),
             (true, false) => write!(f, "({}{})", part, whorl_strings.join("+")),
             (false, _) => write!(f, "{}{}", part, whorl_strings.join("+")),
+        }?;
+
+        // hypanthium, placentation, locules, ovules and styles are
+        // annotated after the gynoecium, e.g. G(3)⟨axile, 3 loc, ∞ ov⟩
+        let mut annotation = Vec::new();
+        // the nature of the whorls, e.g. T3+3⟨outer sepaloid, inner petaloid⟩,
+        // or just T3+3⟨petaloid⟩ if the whorls are alike
+        let natures: Vec<_> = self.whorls.iter().map(|e| e.nature).collect();
+        match natures.first() {
+            Some(Some(n)) if natures.iter().all(|e| e == &Some(*n)) => {
+                annotation.push(n.to_string())
+            }
+            _ => {
+                for whorl in &self.whorls {
+                    match (whorl.position, whorl.nature) {
+                        (Some(p), Some(n)) => annotation.push(format!("{} {}", p, n)),
+                        (None, Some(n)) => annotation.push(n.to_string()),
+                        _ => (),
+                    }
+                }
+            }
+        }
+        for organ in self.connate_organs_to_show() {
+            annotation.push(format!("{} connate", organ));
+        }
+        if self.hypanthium {
+            annotation.push("hypanthium".to_string());
+        }
+        // apocarpy and syncarpy are shown by the brackets already
+        if let Some(cf @ (CarpelFusion::FreeStyles | CarpelFusion::UnitedStyles)) =
+            self.carpel_fusion
+        {
+            annotation.push(cf.to_string());
+        }
+        if !self.placentation.is_empty() {
+            annotation.push(
+                self.placentation
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("/"),
+            );
+        }
+        if let Some(locules) = &self.locules {
+            annotation.push(format!("{} loc", locules));
+        }
+        if let Some(ovules) = &self.ovules {
+            annotation.push(format!("{} ov", ovules));
+        }
+        if let Some(stigmas) = &self.stigmas {
+            annotation.push(format!("{} stig", stigmas));
+        }
+        // and the stamen arrangements after the androecium,
+        // e.g. A(10)⟨monadelphous⟩
+        for arrangement in &self.stamen_arrangement {
+            annotation.push(arrangement.to_string());
+        }
+        if let Some(position) = self.stamen_position {
+            annotation.push(position.to_string());
+        }
+        if annotation.is_empty() {
+            Ok(())
+        } else {
+            write!(f, "⟨{}⟩", annotation.join(", "))
         }
     }
 }
@@ -1043,6 +2392,23 @@ impl FloralPart {
     pub fn add_whorl(&mut self, whorl: Whorl) {
         self.whorls.push(whorl);
     }
+    /// Mark the whorls of a perianth part (T, K or C) as outer,
+    /// middle or inner, if there is more than one whorl.
+    pub fn position_whorls(&mut self) {
+        let perianth = matches!(self.part, Part::Tepals | Part::Calyx | Part::Petals);
+        if !perianth || self.whorls.len() < 2 {
+            return;
+        }
+        let last = self.whorls.len() - 1;
+        for (i, whorl) in self.whorls.iter_mut().enumerate() {
+            let position = match i {
+                0 => WhorlPosition::Outer,
+                i if i == last => WhorlPosition::Inner,
+                _ => WhorlPosition::Middle,
+            };
+            whorl.set_position(Some(position));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1083,7 +2449,7 @@ mod tests {
         // order, family, flower type, symmetry, tepals, calyx, petals, anthers, carpels, ovary, fruit, adnation
         let floral_string = "test2,test2,b,r,2,-,-,2,2,i,berry,-";
         let fs = floral_from_test_str(floral_string);
-        assert_eq!(fs.to_string(), "*,T2,A2,\u{305}G2;berry")
+        assert_eq!(fs.to_string(), "*,T2,A2,G\u{305}2;berry")
     }
     #[test]
     fn test_3() {
@@ -1094,7 +2460,7 @@ mod tests {
         assert_eq!(
             fs.to_string(),
             "\
-*,T2,A2,\u{305}G2;berry
+*,T2,A2,G\u{305}2;berry
   ╰──┴──╯"
         )
     }
@@ -1107,7 +2473,7 @@ mod tests {
         assert_eq!(
             fs.to_string(),
             "\
-*,(T2),(A2),(\u{305}G2);berry
+*,(T2),(A2),(G\u{305}2);berry
    ╰────┴────╯"
         )
     }
@@ -1121,7 +2487,7 @@ mod tests {
         assert_eq!(
             fs.to_string(),
             "\
-*,(T2),(A2+5•),(\u{305}G2);berry
+*,(T2),(A2+5•),(G\u{305}2);berry
    ╰────┴───────╯"
         )
     }
@@ -1136,7 +2502,7 @@ mod tests {
         assert_eq!(
             fs.to_string(),
             "\
-*,T2[or K2,C2],A2,\u{305}G2;berry
+*,T2[or K2,C2],A2,G\u{305}2;berry
   ╰────────────┴──╯"
         )
     }
@@ -1153,4 +2519,517 @@ mod tests {
   ╰──────────╯"
         )
     }
+    #[test]
+    fn test_8() {
+        // placentation, locules and ovules on the carpels
+        // these are extra columns, so look them up from a header
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,placentation,locules,ovules"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test8,test8,b,r,6,-,-,6,3;c,s,capsule,-,a,3,1-inf";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "*,T6,A6,(G\u{332}3)⟨axile, 3 loc, 1-∞ ov⟩;capsule"
+        )
+    }
+    #[test]
+    fn test_9() {
+        // a half-inferior ovary in a hypanthium
+        use super::Ovary;
+        use crate::explain::ExplainFloralFormula;
+        // order, family, flower type, symmetry, tepals, calyx, petals, anthers, carpels, ovary, fruit, adnation
+        let floral_string = "test9,test9,b,r,-,5,5,5,2;c,h;hyp,capsule,-";
+        let fs = floral_from_test_str(floral_string);
+        assert_eq!(fs.to_string(), "*,K5,C5,A5,(G\u{335}2)⟨hypanthium⟩;capsule");
+
+        // a position which varies in the group keeps every position,
+        // which isn't the same as half-inferior
+        let floral_string = "test9,test9,b,r,-,5,5,5,2;c,i;s;h;hyp,capsule,-";
+        let fs = floral_from_test_str(floral_string);
+        let carpels = fs.get_carpels().as_ref().unwrap();
+        assert_eq!(
+            carpels.get_ovary(),
+            &vec![Ovary::Superior, Ovary::HalfInferior, Ovary::Inferior]
+        );
+        assert_eq!(
+            fs.to_string(),
+            "*,K5,C5,A5,(G\u{332}\u{335}\u{305}2)⟨hypanthium⟩;capsule"
+        );
+        let explanation = carpels.explain();
+        assert!(explanation.contains(
+            "The ovary position varies in the group: a superior ovary, or a half-inferior"
+        ));
+        assert!(!explanation.contains("Whorl has a half-inferior"));
+    }
+    #[test]
+    fn test_10() {
+        // fused ovaries with free styles, and apocarpy overriding
+        // the connation given in the carpels column
+        use super::CarpelFusion;
+        use std::str::FromStr;
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,carpel_fusion,stigmas"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test10,test10,b,r,-,5,5,10,5,s,capsule,-,fs,5";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "*,K5,C5,A10,(G\u{332}5)⟨styles free, 5 stig⟩;capsule"
+        );
+
+        let floral_string = "test10,test10,b,r,-,5,5,10,5;c,s,follicle,-,a,-";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(fs.to_string(), "*,K5,C5,A10,G\u{332}5;follicle");
+
+        // the names work as well as the codes, and as they're printed
+        for fusion in [
+            CarpelFusion::Apocarpous,
+            CarpelFusion::Syncarpous,
+            CarpelFusion::FreeStyles,
+            CarpelFusion::UnitedStyles,
+        ] {
+            assert_eq!(CarpelFusion::from_str(&fusion.to_string()).unwrap(), fusion);
+        }
+        assert_eq!(
+            CarpelFusion::from_str("free styles").unwrap(),
+            CarpelFusion::FreeStyles
+        );
+        assert!(CarpelFusion::from_str("fused").is_err());
+    }
+    #[test]
+    fn test_11() {
+        // accessory structures, listed after the gynoecium
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,accessory"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test11,test11,b,r,6;c,-,-,6,3;c,i,capsule,-,sep;co";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "*,(T6),A6,(G\u{305}3){septal nectaries,corona};capsule"
+        );
+    }
+    #[test]
+    fn test_12() {
+        // an epicalyx outside the calyx, adnate to the calyx
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,outer_whorl"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test12,test12,b,r,-,5,5,inf,5,s,capsule,EpK;K,EpK;3";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "\
+*,EpK3,K5,C5,A∞,G\u{332}5;capsule
+  ╰────╯"
+        );
+    }
+    #[test]
+    fn test_13() {
+        // named flower morphs within a family are kept apart
+        use super::{Flower, FlowerType};
+        let data = crate::parse::parse_data().unwrap();
+        let ray = Flower::new(FlowerType::Carpellate, Some("ray floret".into()));
+        let disc = Flower::new(FlowerType::Bisexual, Some("disc floret".into()));
+        assert!(data.contains_key(&("Asterales", "asteraceae", ray.clone())));
+        assert!(data.contains_key(&("Asterales", "asteraceae", disc)));
+        assert_eq!(ray.to_string(), "Carpellate (ray floret)");
+    }
+    #[test]
+    fn test_14() {
+        // fruits keep their modifiers, and can be queried by attributes
+        use super::{FruitDescription, FruitQuery};
+        use std::str::FromStr;
+        let floral_string = "test14,test14,c,r,3,-,-,0,1,s,fleshy capsule;achene,-";
+        let fs = floral_from_test_str(floral_string);
+        assert_eq!(fs.to_string(), "*,T3,A0,G\u{332}1;fleshy capsule,achene");
+
+        let fleshy = FruitDescription::from_str("fleshy capsule").unwrap();
+        let capsule = FruitDescription::from_str("capsule").unwrap();
+        let query = FruitQuery::from_str("fleshy dehiscent").unwrap();
+        assert!(query.matches(&fleshy));
+        assert!(!query.matches(&capsule));
+        let query = FruitQuery::from_str("multiple fruits").unwrap();
+        assert!(query.matches(&FruitDescription::from_str("syconium").unwrap()));
+    }
+    #[test]
+    fn test_15() {
+        // seed characters follow the fruit, and read back
+        // from what is displayed
+        use super::{Endosperm, Seed};
+        use std::str::FromStr;
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,seed"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test15,test15,b,r,-,5,5,5,3;c,s,capsule,-,e;ar;c2";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "*,K5,C5,A5,(G\u{332}3);capsule;seed(endosperm, arillate, 2 cot)"
+        );
+
+        let seed = Seed::from_str("no endosperm, winged, 1 cot").unwrap();
+        assert_eq!(seed.get_endosperm(), Some(Endosperm::Absent));
+        assert!(seed.get_winged() && !seed.get_aril());
+        assert_eq!(seed.to_string(), "no endosperm, winged, 1 cot");
+        assert!(Seed::from_str("c").is_err());
+        assert!(Seed::from_str("e;c").is_err());
+        assert!(Seed::from_str("endosperm,  cot").is_err());
+    }
+    #[test]
+    fn test_16() {
+        // stamen arrangements are annotated after the androecium
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,stamen_arrangement"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test16,test16,b,r,-,5,5;c,5;c,2;c,i,cypsela,-,sy;ep";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "*,K5,(C5),(A5)⟨syngenesious, epipetalous⟩,(G\u{305}2);cypsela"
+        );
+    }
+    #[test]
+    fn test_17() {
+        // filaments connate, and styles connate with free ovaries
+        use crate::explain::ExplainFloralFormula;
+        let floral_string = "test17,test17,b,r,-,5,5,10;cf,5;cst,s,capsule,-";
+        let fs = floral_from_test_str(floral_string);
+        assert_eq!(
+            fs.to_string(),
+            "*,K5,C5,(A10)⟨filaments connate⟩,G\u{332}5⟨styles connate⟩;capsule"
+        );
+        // anthers can't be in the gynoecium
+        let res =
+            crate::parse::floral_from_str("r", "-", "5", "5", "5", "2;ca", "s", "capsule", "-");
+        assert!(res.is_err());
+        // syngenesious says the anthers are connate already
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,stamen_arrangement"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test17,test17,b,r,-,5,5;c,5;ca,2;c,i,cypsela,-,sy";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.to_string(),
+            "*,K5,(C5),(A5)⟨syngenesious⟩,(G\u{305}2);cypsela"
+        );
+        let stamens = fs.get_stamens().as_ref().unwrap();
+        assert_eq!(stamens.get_connate_organs().len(), 1);
+        assert!(!stamens.explain().contains("Connation is of the anthers"));
+    }
+    #[test]
+    fn test_18() {
+        // outer and inner tepal whorls which differ, and which don't
+        let floral_string = "test18,test18,b,r,3k;3p,-,-,6,3;c,s,capsule,-";
+        let fs = floral_from_test_str(floral_string);
+        assert_eq!(
+            fs.to_string(),
+            "*,T3+3⟨outer sepaloid, inner petaloid⟩,A6,(G\u{332}3);capsule"
+        );
+        let floral_string = "test18,test18,b,r,3p;3p,-,-,6,3;c,s,capsule,-";
+        let fs = floral_from_test_str(floral_string);
+        assert_eq!(fs.to_string(), "*,T3+3⟨petaloid⟩,A6,(G\u{332}3);capsule");
+        let whorls = fs.get_tepals().as_ref().unwrap().get_whorls();
+        assert_eq!(whorls[0].get_position(), Some(super::WhorlPosition::Outer));
+        assert_eq!(whorls[1].get_position(), Some(super::WhorlPosition::Inner));
+        // three perianth whorls, and stamen whorls which aren't positioned
+        use std::str::FromStr;
+        let fs = Formula::from_str("*,T3+3+3,A5•+1,G3").unwrap();
+        let whorls = fs.get_tepals().as_ref().unwrap().get_whorls();
+        assert_eq!(whorls[1].get_position(), Some(super::WhorlPosition::Middle));
+        assert_eq!(whorls[2].get_position(), Some(super::WhorlPosition::Inner));
+        let stamens = fs.get_stamens().as_ref().unwrap().get_whorls();
+        assert!(stamens.iter().all(|w| w.get_position().is_none()));
+    }
+    #[test]
+    fn test_19() {
+        // merosity from the perianth, reading T6 as T3+3
+        use super::Merosity;
+        use std::str::FromStr;
+        let fs = floral_from_test_str("test19,test19,b,r,6,-,-,6,3;c,s,capsule,-");
+        assert_eq!(fs.merosity(), Merosity::Merous(3));
+        let fs = floral_from_test_str("test19,test19,b,r,-,4,4,6,2;c,s,silique,-");
+        assert_eq!(fs.merosity(), Merosity::Merous(4));
+        let fs = floral_from_test_str("test19,test19,b,r,-,4-5,5,10,2;c,s,capsule,-");
+        assert_eq!(fs.merosity(), Merosity::Variable);
+        // no perianth, so go by the stamens
+        let fs = floral_from_test_str("test19,test19,s,r,0,-,-,5,0,-,-,-");
+        assert_eq!(fs.merosity(), Merosity::Merous(5));
+        assert_eq!(
+            Merosity::from_str("pentamerous").unwrap(),
+            Merosity::Merous(5)
+        );
+        assert_eq!(Merosity::from_str("5").unwrap(), Merosity::Merous(5));
+    }
+    #[test]
+    fn test_20() {
+        // stamens relative to the perianth, with and without positions
+        use crate::analysis::Androecium;
+        let fs = floral_from_test_str("test20,test20,b,r,-,5,5,5;5,5;c,s,capsule,-");
+        assert_eq!(fs.androecium(), Androecium::Diplostemonous);
+        let fs = floral_from_test_str("test20,test20,b,r,6,-,-,3,3;c,i,capsule,-");
+        assert_eq!(fs.androecium(), Androecium::Haplostemonous);
+        let fs = floral_from_test_str("test20,test20,b,r,-,5,5,inf,3;c,s,capsule,-");
+        assert_eq!(fs.androecium(), Androecium::Polyandrous);
+        let fs = floral_from_test_str("test20,test20,b,r,-,5,5;c,2,2;c,s,capsule,-");
+        assert_eq!(fs.androecium(), Androecium::Oligandrous);
+
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,stamen_position"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test20,test20,b,r,-,5,5,5;5,5;c,s,capsule,-,opp";
+        let record = crate::parse::Record::new(&header, floral_string).unwrap();
+        let fs = crate::parse::floral_from_record(&record).unwrap();
+        assert_eq!(fs.androecium(), Androecium::Obdiplostemonous);
+        // no perianth to compare to
+        let fs = floral_from_test_str("test20,test20,s,r,0,-,-,5,0,-,-,-");
+        assert_eq!(fs.androecium(), Androecium::Indeterminate);
+        // a sterile whorl isn't counted as stamens, as in Costaceae
+        use std::str::FromStr;
+        let fs = Formula::from_str("X(↓),K(3),C(3),A5•+1,G̅(3)").unwrap();
+        assert_eq!(fs.androecium(), Androecium::Oligandrous);
+    }
+    #[test]
+    fn test_21() {
+        // explanations in other languages, with the English unchanged
+        use crate::explain::ExplainFloralFormula;
+        use crate::i18n::{format_message, parse_catalog, translate, Locale};
+        let fs = floral_from_test_str("test21,test21,b,r,-,5,5,5,3;c,s,capsule,-");
+        assert_eq!(fs.explain(), fs.explain_in(Locale::En));
+        let es = fs.explain_in(Locale::Es);
+        assert!(es.contains("Explicación de la fórmula floral anterior:"));
+        assert!(es.contains("cápsula"));
+        assert_eq!(translate(Locale::De, "capsule"), "Kapsel");
+        // the article agrees with the connate organ, the morph is translated,
+        // and the annotations are notation, the same in every language
+        let anthers = floral_from_test_str("test21,test21,b,r,-,5,5,5;ca,3;c,s,capsule,-");
+        let es = anthers.explain_in(Locale::Es);
+        assert!(es.contains("La connación es de las anteras"));
+        assert!(es.contains("(A5)⟨anthers connate⟩"));
+        let flower = super::Flower::new(super::FlowerType::Bisexual, Some("disc floret".into()));
+        assert!(flower.explain_in(Locale::Es).contains("flósculo del disco"));
+        // untranslated messages fall through
+        assert_eq!(translate(Locale::Fr, "not a message"), "not a message");
+        assert_eq!(
+            format_message(Locale::En, "between {} and {}", &["1".into(), "2".into()]),
+            "between 1 and 2"
+        );
+
+        let catalog = parse_catalog("# comment\nmsgid \"a {}\"\nmsgstr \"{0} b\"\n").unwrap();
+        assert_eq!(catalog.get("a {}").unwrap(), "{0} b");
+        assert!(parse_catalog("msgstr \"b\"").is_err());
+
+        // every translation is of an English message, with the same placeholders
+        let en = parse_catalog(include_str!("../assets/i18n/en.po")).unwrap();
+        let en_ids = include_str!("../assets/i18n/en.po")
+            .lines()
+            .filter(|e| e.starts_with("msgid "))
+            .count();
+        assert!(en.is_empty() && en_ids > 0);
+        for po in [
+            include_str!("../assets/i18n/es.po"),
+            include_str!("../assets/i18n/de.po"),
+            include_str!("../assets/i18n/fr.po"),
+        ] {
+            let catalog = parse_catalog(po).unwrap();
+            assert_eq!(catalog.len(), en_ids);
+            for (msgid, msgstr) in catalog {
+                assert!(
+                    include_str!("../assets/i18n/en.po").contains(&format!("msgid \"{}\"", msgid))
+                );
+                assert_eq!(msgid.matches("{}").count(), msgstr.matches("{}").count());
+            }
+        }
+    }
+    #[test]
+    fn test_22() {
+        // explanations at each level of detail
+        use super::Ovary;
+        use crate::explain::{ExplainFloralFormula, Verbosity};
+        use crate::i18n::Locale;
+        use std::str::FromStr;
+        let fs = floral_from_test_str("test22,test22,b,r,-,5,5;c,5,2;c,i,berry,-");
+        assert_eq!(
+            fs.explain_with(Locale::En, Verbosity::Brief),
+            "radial, 5 sepals, 5 connate petals, 5 stamens, 2 connate carpels, inferior ovary, berry"
+        );
+        assert_eq!(fs.explain_with(Locale::En, Verbosity::Normal), fs.explain());
+        let full = fs.explain_with(Locale::En, Verbosity::Full);
+        assert!(full.starts_with(&fs.explain()));
+        assert!(full.contains("(floral glossary <term>): radial"));
+        assert!(full.contains("Glossary:"));
+        assert!(full.contains("connate - Fused to another part of the same kind"));
+        assert!(full.contains("inferior - Of an ovary"));
+        assert!(!full.contains("staminode"));
+
+        // everything else has only the one level
+        assert_eq!(
+            Ovary::Inferior.explain_with(Locale::En, Verbosity::Brief),
+            Ovary::Inferior.explain()
+        );
+        assert_eq!(Verbosity::from_str("full").unwrap(), Verbosity::Full);
+        assert!(Verbosity::from_str("loud").is_err());
+        assert_eq!(
+            crate::glossary::lookup("Staminode").unwrap().get_term(),
+            "staminode"
+        );
+    }
+    #[test]
+    fn test_23() {
+        // the glossary, and that explanations only point to terms in it
+        use crate::explain::{ExplainFloralFormula, Verbosity};
+        use crate::glossary::{entries, lookup, lookup_symbol};
+        use crate::i18n::Locale;
+        for entry in entries() {
+            for see in entry.get_see() {
+                assert!(lookup(see).is_some(), "{} -> {}", entry.get_term(), see);
+            }
+        }
+        let staminode = lookup(" Staminode ").unwrap();
+        assert_eq!(staminode.get_symbol(), Some("•"));
+        assert!(staminode.get_see().contains(&"stamen"));
+        let k: Vec<_> = lookup_symbol("K").iter().map(|e| e.get_term()).collect();
+        assert_eq!(k, ["calyx", "sepal"]);
+        assert!(lookup("not a term").is_none());
+
+        for formula in crate::parse::parse_data().unwrap().values() {
+            let full = formula.explain_with(Locale::En, Verbosity::Full);
+            let (_, links) = full.rsplit_once("(floral glossary <term>): ").unwrap();
+            let (links, _) = links.split_once("\n\n").unwrap();
+            for term in links.replace('\n', " ").split(", ") {
+                assert!(lookup(term.trim()).is_some(), "{}", term);
+            }
+        }
+    }
+    #[test]
+    fn test_24() {
+        // formulae typed out in the notation, and errors pointing to where
+        use super::{Accessory, Endosperm, Ovary};
+        use crate::notation::parse_formula;
+        use std::str::FromStr;
+        let fs = Formula::from_str("*,K(5),C(5),A5,G̅(2);capsule").unwrap();
+        assert_eq!(fs.to_string().trim(), "*,(K5),(C5),A5,(G̅2);capsule");
+        assert!(fs.get_petals().as_ref().unwrap().get_connation());
+        assert_eq!(
+            fs.get_carpels().as_ref().unwrap().get_ovary(),
+            &vec![Ovary::Inferior]
+        );
+        // ASCII ovary positions, ranges, whorls, variation and the rest
+        let fs = parse_formula(
+            "* or X(down), EpK3-∞, (T3+3], A(5)+5•, G_(3){disc};fleshy capsule,berry;seed(ne, 2 cot)",
+        )
+        .unwrap();
+        assert_eq!(fs.get_symmetry().len(), 2);
+        assert!(fs.get_outer().is_some());
+        assert_eq!(fs.get_stamens().as_ref().unwrap().get_whorls().len(), 2);
+        assert!(!fs.get_stamens().as_ref().unwrap().get_connation());
+        assert_eq!(fs.get_accessory(), &vec![Accessory::Disc]);
+        assert_eq!(fs.get_fruit().len(), 2);
+        assert_eq!(
+            fs.get_seed().as_ref().unwrap().get_endosperm(),
+            Some(Endosperm::Absent)
+        );
+
+        let err = parse_formula("*,K(5),Q5").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "expected a floral part (EpK, Br, T, K, C, A or G)\n  *,K(5),Q5\n         ^"
+        );
+        // the combining line over the G takes up no space
+        let err = parse_formula("*,G̅(2);capsul").unwrap_err().to_string();
+        assert!(err.ends_with("  *,G̅(2);capsul\n         ^"));
+        assert!(parse_formula("X,K5").is_err());
+        assert!(parse_formula("*,K5,K5").is_err());
+        assert!(parse_formula("*,A^5").is_err());
+        assert!(parse_formula("*,K5 extra").is_err());
+        // whitespace before a comma as well as after
+        assert_eq!(
+            parse_formula("*,T3 , A3 ,G3").unwrap().to_string(),
+            parse_formula("*,T3,A3,G3").unwrap().to_string()
+        );
+        // a perianth which can't be displayed
+        for s in ["*", "*,K5", "*,C5,A5", "*,G(2]", "X(↓),K5", "*,T5,K5"] {
+            assert!(parse_formula(s).is_err(), "{}", s);
+        }
+        let err = parse_formula("*,C5,A5").unwrap_err().to_string();
+        assert!(err.ends_with("  *,C5,A5\n         ^"));
+
+        // the formulae in the database read back in as themselves
+        for formula in crate::parse::parse_data().unwrap().values() {
+            let display = formula.to_string();
+            let first_line = display.lines().next().unwrap();
+            if first_line.contains('⟨') || first_line.contains("[or") {
+                continue;
+            }
+            let parsed = parse_formula(first_line).unwrap();
+            assert_eq!(parsed.to_string().lines().next().unwrap(), first_line);
+        }
+    }
+
+    #[test]
+    fn test_25() {
+        use crate::explain::Verbosity;
+        use crate::i18n::Locale;
+        use crate::notation::parse_formula;
+
+        let formula = parse_formula("*,K(5),C(5),A5,G_(2);capsule").unwrap();
+        let md = formula.explain_markdown(Locale::En, Verbosity::Normal);
+        // the symmetry symbol is escaped, and each part is in a code span
+        assert!(md.starts_with("- The symmetry is radial (\\*)\n"));
+        assert!(md.contains("\n- `(K5)` = "));
+        assert!(md.contains("\n  - Whorl 1: fertile part and has 5 parts\n"));
+        assert!(md.contains("\n- Fruit(s):\n  - capsule - "));
+        assert!(md
+            .lines()
+            .all(|e| e.starts_with("- ") || e.starts_with("  - ")));
+
+        let brief = formula.explain_markdown(Locale::En, Verbosity::Brief);
+        assert_eq!(brief.lines().count(), 1);
+        assert!(brief.starts_with("- radial, 5 connate sepals"));
+
+        assert!(!md.contains("glossary"));
+
+        // the same glossary links and definitions as the text
+        let full = formula.explain_markdown(Locale::En, Verbosity::Full);
+        assert!(full.contains("\n- Terms in the glossary (floral glossary <term>): radial, "));
+        assert!(full.contains("\n  - connate - "));
+        assert!(full.contains("(see also "));
+    }
+
+    #[test]
+    fn test_26() {
+        // inflorescences, from their column
+        use super::Inflorescence;
+        use crate::parse::{floral_from_record, Record};
+        use std::str::FromStr;
+
+        let header = "order,family,flower_type,symmetry,tepals,calyx,petals,anthers,carpels,ovary,fruit,adnation,inflorescence"
+            .split(',')
+            .collect::<Vec<&str>>();
+        let floral_string = "test31,test31,b,r,-,5,5,5,2;c,i,schizocarp,-,umbel;cyme";
+        let record = Record::new(&header, floral_string).unwrap();
+        let fs = floral_from_record(&record).unwrap();
+        assert_eq!(
+            fs.get_inflorescence(),
+            &vec![Inflorescence::Umbel, Inflorescence::Cyme]
+        );
+        assert_eq!(
+            Inflorescence::from_str("Umbel").unwrap(),
+            Inflorescence::Umbel
+        );
+        assert_eq!(
+            Inflorescence::from_str("HEAD").unwrap(),
+            Inflorescence::Capitulum
+        );
+        assert!(Inflorescence::from_str("umbrella").is_err());
+    }
 }
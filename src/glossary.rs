@@ -0,0 +1,83 @@
+use std::sync::OnceLock;
+
+// the glossary from our assets folder.
+const GLOSSARY: &str = include_str!("../assets/glossary.tsv");
+
+/// A term in the glossary, along with its definition, the
+/// symbol used for it in a formula, and related terms.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    term: &'static str,
+    symbol: Option<&'static str>,
+    see: Vec<&'static str>,
+    definition: &'static str,
+}
+
+impl Entry {
+    /// Get the term
+    pub fn get_term(&self) -> &'static str {
+        self.term
+    }
+    /// Get the symbol used in a formula, if there is one
+    pub fn get_symbol(&self) -> Option<&'static str> {
+        self.symbol
+    }
+    /// Get the related terms
+    pub fn get_see(&self) -> &Vec<&'static str> {
+        &self.see
+    }
+    /// Get the definition
+    pub fn get_definition(&self) -> &'static str {
+        self.definition
+    }
+}
+
+/// All of the entries in the glossary, in alphabetical order.
+pub fn entries() -> &'static [Entry] {
+    static ENTRIES: OnceLock<Vec<Entry>> = OnceLock::new();
+    ENTRIES.get_or_init(|| {
+        let mut lines = GLOSSARY.lines();
+        let header: Vec<_> = lines.next().unwrap_or_default().split('\t').collect();
+        let column = |name: &str| header.iter().position(|e| *e == name);
+        let (term, symbol, see, definition) = match (
+            column("term"),
+            column("symbol"),
+            column("see"),
+            column("definition"),
+        ) {
+            (Some(t), Some(s), Some(r), Some(d)) => (t, s, r, d),
+            _ => return Vec::new(),
+        };
+
+        lines
+            .filter_map(|line| {
+                let fields: Vec<_> = line.split('\t').collect();
+                let field = |idx: usize| fields.get(idx).copied().filter(|e| *e != "-");
+                Some(Entry {
+                    term: field(term)?,
+                    symbol: field(symbol),
+                    see: field(see)
+                        .map(|e| e.split(';').collect())
+                        .unwrap_or_default(),
+                    definition: field(definition)?,
+                })
+            })
+            .collect()
+    })
+}
+
+/// Look up a term in the glossary, ignoring case.
+pub fn lookup(term: &str) -> Option<&'static Entry> {
+    entries()
+        .iter()
+        .find(|e| e.term.eq_ignore_ascii_case(term.trim()))
+}
+
+/// Look up the terms which a symbol in a formula stands for,
+/// e.g. `K` for the calyx and the sepals.
+pub fn lookup_symbol(symbol: &str) -> Vec<&'static Entry> {
+    entries()
+        .iter()
+        .filter(|e| e.symbol == Some(symbol.trim()))
+        .collect()
+}
@@ -0,0 +1,196 @@
+use crate::error::{Error, ErrorKind};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// The message catalogs. English is the language the messages
+/// are written in, so its catalog is the template for the others.
+const EN: &str = include_str!("../assets/i18n/en.po");
+const ES: &str = include_str!("../assets/i18n/es.po");
+const DE: &str = include_str!("../assets/i18n/de.po");
+const FR: &str = include_str!("../assets/i18n/fr.po");
+
+/// The languages which an explanation can be given in. The formulae
+/// themselves, their ⟨...⟩ annotations included, are notation and
+/// aren't translated, nor are the glossary terms, which are what
+/// `floral glossary` looks them up by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    De,
+    Fr,
+}
+
+impl Locale {
+    /// All of the supported locales
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::Es, Locale::De, Locale::Fr];
+
+    // the raw message catalog for this locale
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::En => EN,
+            Locale::Es => ES,
+            Locale::De => DE,
+            Locale::Fr => FR,
+        }
+    }
+
+    // the parsed message catalog for this locale, parsed once
+    fn catalog(&self) -> &'static HashMap<String, String> {
+        static CATALOGS: [OnceLock<HashMap<String, String>>; 4] = [
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+        ];
+        let idx = Locale::ALL.iter().position(|e| e == self).unwrap_or(0);
+        CATALOGS[idx].get_or_init(|| parse_catalog(self.source()).unwrap_or_default())
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locale::En => write!(f, "en"),
+            Locale::Es => write!(f, "es"),
+            Locale::De => write!(f, "de"),
+            Locale::Fr => write!(f, "fr"),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Self::En),
+            "es" | "spanish" | "español" => Ok(Self::Es),
+            "de" | "german" | "deutsch" => Ok(Self::De),
+            "fr" | "french" | "français" => Ok(Self::Fr),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "language: {}, not recognised (try en, es, de or fr)",
+                other
+            )))),
+        }
+    }
+}
+
+// undo the escapes in a quoted catalog string
+fn unescape(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next()? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                other => out.push(other),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    Some(out)
+}
+
+/// Parse a message catalog. These are a small subset of the gettext
+/// `.po` format: pairs of `msgid "..."` and `msgstr "..."` lines, with
+/// `#` comments. An empty `msgstr` means the message is not translated.
+pub fn parse_catalog(s: &str) -> crate::error::Result<HashMap<String, String>> {
+    let mut catalog = HashMap::new();
+    let mut msgid: Option<String> = None;
+
+    for (no, line) in s.lines().enumerate() {
+        let line = line.trim();
+        let bad_line = || {
+            Error::new(ErrorKind::ParseError(format!(
+                "message catalog, line {}: {}",
+                no + 1,
+                line
+            )))
+        };
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("msgid ") {
+            msgid = Some(unescape(rest).ok_or_else(bad_line)?);
+        } else if let Some(rest) = line.strip_prefix("msgstr ") {
+            let id = msgid.take().ok_or_else(bad_line)?;
+            let msgstr = unescape(rest).ok_or_else(bad_line)?;
+            if !msgstr.is_empty() {
+                catalog.insert(id, msgstr);
+            }
+        } else {
+            return Err(bad_line());
+        }
+    }
+    Ok(catalog)
+}
+
+/// Translate a message, falling back to the message itself.
+pub fn translate(locale: Locale, msgid: &str) -> String {
+    match locale.catalog().get(msgid) {
+        Some(msgstr) => msgstr.clone(),
+        None => msgid.to_string(),
+    }
+}
+
+/// Translate a message with `{}` placeholders, and fill them in with
+/// the arguments in order. A translation can reorder the arguments
+/// with `{0}`, `{1}` and so on.
+pub fn format_message(locale: Locale, msgid: &str, args: &[String]) -> String {
+    let template = translate(locale, msgid);
+    let mut out = String::new();
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            out.push(c);
+            continue;
+        }
+        let mut index = String::new();
+        while let Some(d) = chars.next_if(|e| e.is_ascii_digit()) {
+            index.push(d);
+        }
+        if chars.next_if_eq(&'}').is_none() {
+            // not a placeholder after all
+            out.push('{');
+            out += &index;
+            continue;
+        }
+        let idx = match index.parse::<usize>() {
+            Ok(i) => i,
+            Err(_) => {
+                next += 1;
+                next - 1
+            }
+        };
+        if let Some(arg) = args.get(idx) {
+            out += arg;
+        }
+    }
+    out
+}
+
+/// Translate a message, filling in the arguments. The arguments are
+/// themselves translated if they are in the catalog, as they are
+/// often the names of things, e.g. `capsule`.
+macro_rules! t {
+    ($locale:expr, $msgid:expr) => {
+        $crate::i18n::translate($locale, $msgid)
+    };
+    ($locale:expr, $msgid:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format_message(
+            $locale,
+            $msgid,
+            &[$($crate::i18n::translate($locale, &$arg.to_string())),+],
+        )
+    };
+}
+pub(crate) use t;
@@ -16,6 +16,12 @@
 //!
 //! This is just a personal side project. Please do get involved and use if it is useful!
 
+/// Analyses of a [`Formula`](floral::Formula) which go beyond what is written in it,
+/// such as the number of stamens relative to the perianth.
+pub mod analysis;
+/// Completion scripts for bash, zsh and fish, which complete family
+/// and order names from the database.
+pub mod completions;
 /// An error module to encompass the main errors that might occur when parsing, or
 /// attempting to display a floral formula.
 pub mod error;
@@ -25,8 +31,29 @@ pub mod explain;
 /// The main module containing all of the typed parts of a floral formula and mainly
 /// [`Display`](std::fmt::Display) implementations on each of these.
 pub mod floral;
+/// A glossary of the terms used in the explanations, bundled
+/// from `./assets/glossary.tsv`.
+pub mod glossary;
+/// Translations of the explanations, using message catalogs
+/// bundled from `./assets/i18n`.
+pub mod i18n;
+/// Parse a floral formula typed out in the usual notation
+/// into the [`Formula`](floral::Formula) object.
+pub mod notation;
+/// The formats which the output can be printed in, and a table
+/// of output which can be rendered in any of them.
+pub mod output;
 /// Parse the input from the database into the [`Formula`] object.
 pub mod parse;
+/// Multiple choice questions about the formulae in the
+/// database, for revision.
+pub mod quiz;
+/// An interactive shell, with history and tab completion, which
+/// runs the commands of the CLI on a database parsed once.
+pub mod shell;
+/// Statistics over the formulae in the database, such as how
+/// many families have each kind of symmetry.
+pub mod stats;
 
 /// Command line parsing specific to the tool
 pub mod cli;
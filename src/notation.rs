@@ -0,0 +1,422 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::floral::{
+    Accessory, BilateralType, FloralPart, FloralPartNumber, Formula, Fruit, FruitDescription,
+    Ovary, Part, Seed, Symmetry, Whorl,
+};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Parse a floral formula written out in the usual notation, e.g.
+/// `*,K(5),C(5),A5,G(2);capsule`. This is the notation the formulae
+/// are displayed in, less the adnation line and the annotations, so:
+///
+/// - the symmetry comes first: `*`, `X(↓)` (or `X(down)`), `↯`, `↻`
+///   or `↔`, with alternatives separated by `or`.
+/// - then the floral parts, separated by commas: `EpK`, `Br`, `T` (or
+///   `P`), `K`, `C`, `A` and `G`. Brackets around a part, e.g. `(C5)`,
+///   mean it is connate, and `(C5]` that the connation varies. `C(5)` is
+///   the same as `(C5)`, but with more than one whorl the brackets are
+///   for that whorl alone, e.g. `A(5)+5`. Whorls are joined by `+`, and
+///   a whorl can be a range, e.g. `3-6`, `∞` (or `inf`), or sterile (`•`).
+///   The perianth is tepals, or a calyx and a corolla, e.g. `K5,C5`.
+/// - the ovary position follows the `G`, as a line under (superior), over
+///   (inferior) or through (half-inferior) it. These can also be typed
+///   as `G_`, `G^` or `G~`.
+/// - accessory structures can follow a part in braces, e.g. `{disc}`.
+/// - the fruits come after a `;`, separated by commas, and then the seed,
+///   e.g. `;capsule;seed(endosperm, 2 cot)`.
+pub fn parse_formula(s: &str) -> Result<Formula> {
+    Parser::new(s).formula()
+}
+
+impl FromStr for Formula {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_formula(s)
+    }
+}
+
+// the lines over, under and through the G for the ovary position
+const UNDERLINE: char = '\u{332}';
+const OVERLINE: char = '\u{305}';
+const STRIKETHROUGH: char = '\u{335}';
+
+// combining marks take up no space of their own in the terminal
+pub(crate) fn is_combining(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+    accessory: Vec<Accessory>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.chars().collect(),
+            pos: 0,
+            accessory: Vec::new(),
+        }
+    }
+
+    // an error with the formula, and a caret under where it went wrong
+    fn error(&self, pos: usize, message: impl Display) -> Error {
+        let column = self.chars[..pos.min(self.chars.len())]
+            .iter()
+            .filter(|e| !is_combining(**e))
+            .count();
+        Error::new(ErrorKind::ParseError(format!(
+            "{}\n  {}\n  {}^",
+            message,
+            self.input,
+            " ".repeat(column)
+        )))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let len = s.chars().count();
+        let matches = self.chars.len() >= self.pos + len
+            && self.chars[self.pos..self.pos + len]
+                .iter()
+                .copied()
+                .eq(s.chars());
+        if matches {
+            self.pos += len;
+        }
+        matches
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|e| e.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    // everything up to (not including) any of the stop characters
+    fn take_until(&mut self, stop: &[char]) -> (usize, String) {
+        let start = self.pos;
+        while self.peek().is_some_and(|e| !stop.contains(&e)) {
+            self.pos += 1;
+        }
+        (start, self.chars[start..self.pos].iter().collect())
+    }
+
+    fn formula(mut self) -> Result<Formula> {
+        let mut symmetry = vec![self.symmetry()?];
+        loop {
+            self.skip_whitespace();
+            if !self.eat_str("or") {
+                break;
+            }
+            self.skip_whitespace();
+            symmetry.push(self.symmetry()?);
+        }
+
+        let mut formula = Formula::default().with_symmetry(symmetry);
+        let mut seen = Vec::new();
+        loop {
+            // whitespace either side of the comma
+            self.skip_whitespace();
+            if !self.eat(',') {
+                break;
+            }
+            self.skip_whitespace();
+            let start = self.pos;
+            let part = self.floral_part()?;
+            if seen.contains(&part.get_part()) {
+                return Err(self.error(
+                    start,
+                    format!("{} is given more than once", part.get_part()),
+                ));
+            }
+            seen.push(part.get_part());
+            formula = match part.get_part() {
+                Part::Epicalyx | Part::Bracteoles => formula.with_outer(Some(part)),
+                Part::Tepals => formula.with_tepals(Some(part)),
+                Part::Calyx => formula.with_sepals(Some(part)),
+                Part::Petals => formula.with_petals(Some(part)),
+                Part::Stamens => formula.with_stamens(Some(part)),
+                Part::Carpels => formula.with_carpels(Some(part)),
+            };
+        }
+        let parts_end = self.pos;
+
+        let mut fruits = Vec::new();
+        let mut seed = None;
+        if self.eat(';') {
+            fruits = self.fruits()?;
+            if self.eat(';') {
+                seed = Some(self.seed()?);
+            }
+        }
+        if let Some(c) = self.peek() {
+            return Err(self.error(self.pos, format!("unexpected '{}'", c)));
+        }
+        // the perianth is either tepals, or a calyx and a corolla
+        let tepals = seen.contains(&Part::Tepals);
+        let calyx = seen.contains(&Part::Calyx);
+        if calyx != seen.contains(&Part::Petals) || !(tepals || calyx) {
+            return Err(self.error(
+                parts_end,
+                "expected tepals (T), or both a calyx (K) and a corolla (C)",
+            ));
+        }
+        if fruits.is_empty() {
+            fruits.push(Fruit::None.into());
+        }
+
+        Ok(formula
+            .with_fruit(fruits)
+            .with_accessory(self.accessory)
+            .with_seed(seed)
+            .build())
+    }
+
+    fn symmetry(&mut self) -> Result<Symmetry> {
+        let start = self.pos;
+        match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Ok(Symmetry::Radial)
+            }
+            Some('↯') => {
+                self.pos += 1;
+                Ok(Symmetry::Asymmetry)
+            }
+            Some('↻') => {
+                self.pos += 1;
+                Ok(Symmetry::Spiral)
+            }
+            Some('↔') => {
+                self.pos += 1;
+                Ok(Symmetry::Disymmetric)
+            }
+            Some('X') => {
+                self.pos += 1;
+                if !self.eat('(') {
+                    return Err(
+                        self.error(self.pos, "bilateral symmetry needs a direction, e.g. X(↓)")
+                    );
+                }
+                let (dir_start, direction) = self.take_until(&[')']);
+                if !self.eat(')') {
+                    return Err(self.error(self.pos, "expected ) to close the direction"));
+                }
+                let bilateral = match direction.as_str() {
+                    "↑" => BilateralType::Up,
+                    "↓" => BilateralType::Down,
+                    "←" => BilateralType::Left,
+                    "→" => BilateralType::Right,
+                    "↖" => BilateralType::Upleft,
+                    "↗" => BilateralType::Upright,
+                    "↙" => BilateralType::Downleft,
+                    "↘" => BilateralType::Downright,
+                    other => BilateralType::from_str(other).map_err(|_| {
+                        self.error(
+                            dir_start,
+                            format!("direction: {}, not recognised (e.g. ↓ or down)", other),
+                        )
+                    })?,
+                };
+                Ok(Symmetry::Bilateral(bilateral))
+            }
+            _ => Err(self.error(start, "expected a symmetry (*, X(↓), ↯, ↻ or ↔)")),
+        }
+    }
+
+    fn floral_part(&mut self) -> Result<FloralPart> {
+        let connate = self.eat('(');
+
+        let part = if self.eat_str("EpK") {
+            Part::Epicalyx
+        } else if self.eat_str("Br") {
+            Part::Bracteoles
+        } else {
+            let part = match self.peek() {
+                Some('T' | 'P') => Part::Tepals,
+                Some('K') => Part::Calyx,
+                Some('C') => Part::Petals,
+                Some('A') => Part::Stamens,
+                Some('G') => Part::Carpels,
+                _ => {
+                    return Err(self.error(
+                        self.pos,
+                        "expected a floral part (EpK, Br, T, K, C, A or G)",
+                    ))
+                }
+            };
+            self.pos += 1;
+            part
+        };
+
+        let mut floral = FloralPart::default();
+        floral.set_part(part.clone());
+        floral.set_connation(connate);
+
+        // the ovary position
+        let ovary_start = self.pos;
+        let (mut superior, mut inferior, mut half) = (false, false, false);
+        loop {
+            match self.peek() {
+                Some(UNDERLINE | '_') => superior = true,
+                Some(OVERLINE | '^') => inferior = true,
+                Some(STRIKETHROUGH | '~') => half = true,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        // more than one mark is a position which varies in the group
+        let ovary: Vec<Ovary> = [
+            (superior, Ovary::Superior),
+            (half, Ovary::HalfInferior),
+            (inferior, Ovary::Inferior),
+        ]
+        .into_iter()
+        .filter_map(|(marked, ovary)| marked.then_some(ovary))
+        .collect();
+        if !ovary.is_empty() && part != Part::Carpels {
+            return Err(self.error(ovary_start, "only the gynoecium (G) has an ovary position"));
+        }
+        floral.set_ovary(ovary);
+
+        let mut whorls = vec![self.whorl()?];
+        while self.eat('+') {
+            whorls.push(self.whorl()?);
+        }
+        // a single bracketed whorl, e.g. K(5), is how a connate
+        // part is usually written, so it is the same as (K5)
+        if let [whorl] = &mut whorls[..] {
+            if !connate && whorl.get_connation() {
+                floral.set_connation(true);
+                floral.set_connation_variation(whorl.get_connation_variation());
+                whorl.set_connation(false, false);
+            }
+        }
+        for whorl in whorls {
+            floral.add_whorl(whorl);
+        }
+
+        if connate {
+            if self.eat(']') {
+                floral.set_connation_variation(true);
+            } else if !self.eat(')') {
+                return Err(self.error(
+                    self.pos,
+                    format!("expected ) or ] to close the bracket around {}", part),
+                ));
+            }
+        }
+
+        if self.peek() == Some('⟨') {
+            return Err(self.error(
+                self.pos,
+                "annotations (⟨...⟩) can't be typed into a formula",
+            ));
+        }
+        if self.eat('{') {
+            let (acc_start, accessory) = self.take_until(&['}']);
+            if !self.eat('}') {
+                return Err(self.error(self.pos, "expected } to close the accessory structures"));
+            }
+            for acc in accessory.split(',') {
+                let parsed =
+                    Accessory::from_str(acc.trim()).map_err(|e| self.error(acc_start, e))?;
+                self.accessory.push(parsed);
+            }
+        }
+
+        floral.position_whorls();
+        Ok(floral)
+    }
+
+    fn whorl(&mut self) -> Result<Whorl> {
+        let connate = self.eat('(');
+
+        let min = self.number()?;
+        let max = if self.eat('-') {
+            Some(self.number()?)
+        } else {
+            None
+        };
+        let sterile = self.eat('•');
+
+        let variation = if connate {
+            if self.eat(']') {
+                true
+            } else if self.eat(')') {
+                false
+            } else {
+                return Err(self.error(self.pos, "expected ) or ] to close the whorl"));
+            }
+        } else {
+            false
+        };
+
+        Ok(match max {
+            Some(max) => Whorl::new(None, Some(min), Some(max), sterile, connate, variation),
+            None => Whorl::new(Some(min), None, None, sterile, connate, variation),
+        })
+    }
+
+    fn number(&mut self) -> Result<FloralPartNumber> {
+        if self.eat('∞') || self.eat_str("inf") {
+            return Ok(FloralPartNumber::Infinite);
+        }
+        if self.eat('½') {
+            return Ok(FloralPartNumber::Fractional(0.5));
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|e| e.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error(start, "expected a number of parts (e.g. 5, 3-6 or ∞)"));
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        FloralPartNumber::from_str(&digits).map_err(|e| self.error(start, e))
+    }
+
+    fn fruits(&mut self) -> Result<Vec<FruitDescription>> {
+        let mut fruits = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let (start, fruit) = self.take_until(&[',', ';']);
+            let fruit =
+                FruitDescription::from_str(fruit.trim()).map_err(|e| self.error(start, e))?;
+            fruits.push(fruit);
+            if !self.eat(',') {
+                return Ok(fruits);
+            }
+        }
+    }
+
+    fn seed(&mut self) -> Result<Seed> {
+        self.skip_whitespace();
+        if !self.eat_str("seed(") {
+            return Err(self.error(self.pos, "expected the seed, e.g. seed(endosperm, 2 cot)"));
+        }
+        let (start, seed) = self.take_until(&[')']);
+        if !self.eat(')') {
+            return Err(self.error(self.pos, "expected ) to close the seed"));
+        }
+        Seed::from_str(&seed).map_err(|e| self.error(start, e))
+    }
+}
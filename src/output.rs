@@ -0,0 +1,345 @@
+use crate::error::{Error, ErrorKind};
+use crate::notation::is_combining;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The formats which output can be printed in. Text and Markdown
+/// are for reading, and TSV, CSV and JSON are for other programs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Markdown,
+    Tsv,
+    Csv,
+    Json,
+}
+
+impl OutputFormat {
+    /// Whether this format is a table of data, rather than
+    /// something to be read.
+    pub fn is_tabular(&self) -> bool {
+        matches!(self, Self::Tsv | Self::Csv | Self::Json)
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Markdown => write!(f, "markdown"),
+            OutputFormat::Tsv => write!(f, "tsv"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "tsv" => Ok(Self::Tsv),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "output format: {}, not recognised (try text, markdown, tsv, csv or json)",
+                other
+            )))),
+        }
+    }
+}
+
+/// A single cell in a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(usize),
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Text(s) => write!(f, "{}", s),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", if *b { "yes" } else { "no" }),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.to_string())
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+/// A table of output, with a header naming each column, which can
+/// be rendered in any [`OutputFormat`].
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Constructor for the [`Table`] struct, with the column names
+    pub fn new(header: &[&str]) -> Self {
+        Self {
+            header: header.iter().map(|e| e.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+    /// Add a row to the table
+    pub fn push_row(&mut self, row: Vec<Value>) {
+        self.rows.push(row);
+    }
+    /// Get the column names
+    pub fn get_header(&self) -> &Vec<String> {
+        &self.header
+    }
+    /// Get the rows
+    pub fn get_rows(&self) -> &Vec<Vec<Value>> {
+        &self.rows
+    }
+    /// Whether the table has no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render the table. Each format ends with a newline.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => self.render_text(),
+            OutputFormat::Markdown => self.render_markdown(),
+            OutputFormat::Tsv => self.render_delimited('\t', tsv_field),
+            OutputFormat::Csv => self.render_delimited(',', csv_field),
+            OutputFormat::Json => self.render_json(),
+        }
+    }
+
+    // the header and rows, all as strings
+    fn lines(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        std::iter::once(self.header.clone()).chain(
+            self.rows
+                .iter()
+                .map(|row| row.iter().map(|e| e.to_string()).collect()),
+        )
+    }
+
+    // columns padded out to line up, with the last left ragged
+    fn render_text(&self) -> String {
+        let mut widths = vec![0; self.header.len()];
+        for line in self.lines() {
+            for (width, cell) in widths.iter_mut().zip(&line) {
+                *width = (*width).max(display_width(cell));
+            }
+        }
+        let mut out = String::new();
+        for line in self.lines() {
+            let last = line.len().saturating_sub(1);
+            for (idx, cell) in line.iter().enumerate() {
+                out += cell;
+                if idx < last {
+                    out += &" ".repeat(widths[idx] - display_width(cell) + 2);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn render_markdown(&self) -> String {
+        let row = |cells: Vec<String>| {
+            let cells: Vec<_> = cells.iter().map(|e| e.replace('|', "\\|")).collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+        let mut lines = self.lines();
+        let mut out = row(lines.next().unwrap_or_default());
+        out += &row(vec!["---".to_string(); self.header.len()]);
+        for line in lines {
+            out += &row(line);
+        }
+        out
+    }
+
+    fn render_delimited(&self, delimiter: char, field: fn(&str) -> String) -> String {
+        let mut out = String::new();
+        for line in self.lines() {
+            let fields: Vec<_> = line.iter().map(|e| field(e)).collect();
+            out += &fields.join(&delimiter.to_string());
+            out.push('\n');
+        }
+        out
+    }
+
+    // an array of objects, keyed by the column names
+    fn render_json(&self) -> String {
+        format!("{}\n", self.json_rows(""))
+    }
+
+    // the rows as a json array, with each row on its own line
+    fn json_rows(&self, indent: &str) -> String {
+        if self.rows.is_empty() {
+            return "[]".to_string();
+        }
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<_> = self
+                    .header
+                    .iter()
+                    .zip(row)
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Value::Text(s) => json_string(s),
+                            Value::Number(n) => n.to_string(),
+                            Value::Bool(b) => b.to_string(),
+                        };
+                        format!("{}: {}", json_string(key), value)
+                    })
+                    .collect();
+                format!("{}  {{{}}}", indent, fields.join(", "))
+            })
+            .collect();
+        format!("[\n{}\n{}]", rows.join(",\n"), indent)
+    }
+}
+
+/// Render several named tables together. In JSON they are an object
+/// keyed by name, in Markdown each has a heading, and as text they
+/// follow one another. TSV and CSV have no way to tell where one
+/// table stops and the next starts, so they are blank line separated.
+pub fn render_tables(tables: &[(&str, Table)], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => {
+            let fields: Vec<_> = tables
+                .iter()
+                .map(|(name, table)| format!("  {}: {}", json_string(name), table.json_rows("  ")))
+                .collect();
+            format!("{{\n{}\n}}\n", fields.join(",\n"))
+        }
+        OutputFormat::Markdown => tables
+            .iter()
+            .map(|(name, table)| format!("## {}\n\n{}", name, table.render(format)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        format => tables
+            .iter()
+            .map(|(_, table)| table.render(format))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+// the number of columns a string takes up in the terminal
+fn display_width(s: &str) -> usize {
+    s.chars().filter(|e| !is_combining(*e)).count()
+}
+
+// tabs and newlines would break the columns, so they become spaces
+fn tsv_field(s: &str) -> String {
+    s.replace(['\t', '\n'], " ")
+}
+
+// quote fields with commas, quotes or newlines in, doubling the quotes
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\t' => out += "\\t",
+            '\r' => out += "\\r",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_table_in_each_format() {
+        assert_eq!(
+            OutputFormat::from_str("md").unwrap(),
+            OutputFormat::Markdown
+        );
+        assert!(OutputFormat::from_str("xml").is_err());
+        assert!(OutputFormat::Json.is_tabular());
+
+        let mut table = Table::new(&["family", "fruit", "count", "staminate"]);
+        table.push_row(vec![
+            "araceae".into(),
+            "berry,utricle".into(),
+            Value::from(2),
+            Value::from(false),
+        ]);
+        table.push_row(vec![
+            "iridaceae".into(),
+            "say \"capsule\"".into(),
+            Value::from(1),
+            Value::from(true),
+        ]);
+
+        assert_eq!(
+            table.render(OutputFormat::Tsv),
+            "family\tfruit\tcount\tstaminate\naraceae\tberry,utricle\t2\tno\niridaceae\tsay \"capsule\"\t1\tyes\n"
+        );
+        assert_eq!(
+            table.render(OutputFormat::Csv).lines().nth(2).unwrap(),
+            "iridaceae,\"say \"\"capsule\"\"\",1,yes"
+        );
+        let json = table.render(OutputFormat::Json);
+        assert!(json.contains(
+            r#"{"family": "araceae", "fruit": "berry,utricle", "count": 2, "staminate": false}"#
+        ));
+        assert!(json.contains(r#""fruit": "say \"capsule\"""#));
+        // the columns line up, with the last left ragged
+        let text = table.render(OutputFormat::Text);
+        assert_eq!(
+            text.lines().next().unwrap(),
+            "family     fruit          count  staminate"
+        );
+        assert_eq!(
+            text.lines().nth(1).unwrap(),
+            "araceae    berry,utricle  2      no"
+        );
+        assert_eq!(Table::new(&["a"]).render(OutputFormat::Json), "[]\n");
+    }
+}
@@ -1,7 +1,8 @@
 use crate::error::{Error, ErrorKind, Result};
 use crate::floral::{
-    Adnation, FloralPart, FloralPartNumber, FlowerType, Formula, Fruit, Ovary, Part, Symmetry,
-    Whorl,
+    Accessory, Adnation, CarpelFusion, ConnateOrgan, FloralPart, FloralPartNumber, Flower,
+    FlowerType, Formula, FruitDescription, Inflorescence, Ovary, Part, PartCount, Placentation,
+    Seed, StamenArrangement, StamenPosition, Symmetry, Whorl, WhorlNature,
 };
 use std::collections::BTreeMap as Map;
 use std::str::FromStr;
@@ -9,41 +10,115 @@ use std::str::FromStr;
 // the data from our assets folder.
 pub const DATA: &str = include_str!("../assets/formulae.csv");
 
+/// A single line of the database, where the fields can be
+/// looked up by the column names in the header.
+pub struct Record<'a> {
+    fields: Map<&'a str, &'a str>,
+}
+
+impl<'a> Record<'a> {
+    /// Zip a header with a line of the database. Returns `None`
+    /// if the number of fields does not match the header.
+    pub fn new(header: &[&'a str], line: &'a str) -> Option<Self> {
+        let line_elements = line.split(',').collect::<Vec<&str>>();
+        if line_elements.len() != header.len() {
+            return None;
+        }
+        let fields = header.iter().copied().zip(line_elements).collect();
+        Some(Self { fields })
+    }
+
+    /// Get a field by column name. Columns missing from the
+    /// header are treated as empty (`-`).
+    pub fn get(&self, column: &str) -> &'a str {
+        self.fields.get(column).copied().unwrap_or("-")
+    }
+}
+
 // function to parse the data into a map
-pub fn parse_data<'a>() -> Result<Map<(&'a str, &'a str, FlowerType), Formula>> {
-    // skip headers
-    let lines = DATA.lines().skip(1);
+pub fn parse_data<'a>() -> Result<Map<(&'a str, &'a str, Flower), Formula>> {
+    parse_data_from(DATA)
+}
+
+/// Parse a database in the same format as the bundled one, e.g.
+/// read from a file of the user's own, into a map.
+pub fn parse_data_from(source: &str) -> Result<Map<(&str, &str, Flower), Formula>> {
+    let mut lines = source.lines();
+    let header = lines
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .collect::<Vec<&str>>();
     let mut data_map = Map::new();
     for (mut line_no, line) in lines.enumerate() {
         line_no += 2;
         // this is technically a csv parser, but I don't really want the overhead of a
         // fully blown csv parser yet (e.g. csv crate), though it would be nicer for errors.
-        let line_elements = line.split(',').collect::<Vec<&str>>();
-
-        if let [order, family, flower_type, symmetry, tepals, calyx, petals, anthers, carpels, ovary, fruit, adnation] =
-            &line_elements[..]
-        {
-            let floral = floral_from_str(
-                symmetry, tepals, calyx, petals, anthers, carpels, ovary, fruit, adnation,
-            )
-            .map_err(|e| {
+        if let Some(record) = Record::new(&header, line) {
+            let floral = floral_from_record(&record).map_err(|e| {
                 Error::new(ErrorKind::CSVParseError(format!(
                     "at line {} - {}",
                     line_no, e
                 )))
             })?;
-            let ft = FlowerType::from_str(flower_type).map_err(|e| {
+            let ft = FlowerType::from_str(record.get("flower_type")).map_err(|e| {
                 Error::new(ErrorKind::CSVParseError(format!(
                     "at line {} - {}",
                     line_no, e
                 )))
             })?;
-            data_map.insert((*order, *family, ft), floral);
+            // a named morph, e.g. ray floret, distinguishes several
+            // flowers of the same type within a family
+            let morph = match record.get("morph") {
+                "" | "-" => None,
+                m => Some(m.to_string()),
+            };
+            data_map.insert(
+                (
+                    record.get("order"),
+                    record.get("family"),
+                    Flower::new(ft, morph),
+                ),
+                floral,
+            );
         }
     }
     Ok(data_map)
 }
 
+/// Parse a whole database record into a [`Formula`]. The core
+/// columns are handed to [`floral_from_str`], and the optional
+/// columns are added on top.
+pub fn floral_from_record(record: &Record) -> Result<Formula> {
+    let formula = floral_from_str(
+        record.get("symmetry"),
+        record.get("tepals"),
+        record.get("calyx"),
+        record.get("petals"),
+        record.get("anthers"),
+        record.get("carpels"),
+        record.get("ovary"),
+        record.get("fruit"),
+        record.get("adnation"),
+    )?;
+
+    let stamens = parse_stamen_details(formula.get_stamens().clone(), record)?;
+    let carpels = parse_carpel_details(formula.get_carpels().clone(), record)?;
+
+    let accessory = parse_accessory(record.get("accessory"))?;
+    let outer = parse_outer_whorl(record.get("outer_whorl"))?;
+    let inflorescence = parse_inflorescence(record.get("inflorescence"))?;
+    let seed = parse_seed(record.get("seed"))?;
+
+    Ok(formula
+        .with_stamens(stamens)
+        .with_carpels(carpels)
+        .with_accessory(accessory)
+        .with_outer(outer)
+        .with_inflorescence(inflorescence)
+        .with_seed(seed))
+}
+
 // here we do the heavy lifting parsing the csv
 #[allow(clippy::too_many_arguments)]
 pub fn floral_from_str(
@@ -62,18 +137,22 @@ pub fn floral_from_str(
     let parsed_sym: Result<Vec<Symmetry>> = sym_vec.iter().map(|e| Symmetry::from_str(e)).collect();
     let parsed_sym = parsed_sym?;
 
-    let parsed_ovary = parse_ovary(ovary)?;
+    let (parsed_ovary, hypanthium) = parse_ovary(ovary)?;
 
-    let parsed_tepals = parse_floral_part_to_enum(tepals, Part::Tepals, None)?;
-    let parsed_calyx = parse_floral_part_to_enum(calyx, Part::Calyx, None)?;
-    let parsed_petals = parse_floral_part_to_enum(petals, Part::Petals, None)?;
-    let parsed_anthers = parse_floral_part_to_enum(anthers, Part::Stamens, None)?;
-    let parsed_carpels = parse_floral_part_to_enum(carpels, Part::Carpels, parsed_ovary)?;
+    let parsed_tepals = parse_floral_part_to_enum(tepals, Part::Tepals, Vec::new())?;
+    let parsed_calyx = parse_floral_part_to_enum(calyx, Part::Calyx, Vec::new())?;
+    let parsed_petals = parse_floral_part_to_enum(petals, Part::Petals, Vec::new())?;
+    let parsed_anthers = parse_floral_part_to_enum(anthers, Part::Stamens, Vec::new())?;
+    let parsed_carpels =
+        parse_floral_part_to_enum(carpels, Part::Carpels, parsed_ovary)?.map(|mut c| {
+            c.set_hypanthium(hypanthium);
+            c
+        });
 
     let parsed_adnation = parse_adnation(adnation)?;
     let parsed_fruit = {
         let sp = fruit.split(';').collect::<Vec<&str>>();
-        let fruits: Result<Vec<_>> = sp.iter().map(|e| Fruit::from_str(e)).collect();
+        let fruits: Result<Vec<_>> = sp.iter().map(|e| FruitDescription::from_str(e)).collect();
         fruits?
     };
 
@@ -91,25 +170,140 @@ pub fn floral_from_str(
     Ok(formula)
 }
 
-fn parse_ovary(s: &str) -> Result<Option<Ovary>> {
+// the ovary column also records whether there is a hypanthium
+// (`hyp`), e.g. s;hyp is a superior ovary in a floral cup.
+fn parse_ovary(s: &str) -> Result<(Vec<Ovary>, bool)> {
     if s.is_empty() || s == "-" {
-        return Ok(None);
+        return Ok((Vec::new(), false));
     }
 
     let sp = s.split(';').collect::<Vec<&str>>();
     let mut ov_vec = Vec::new();
+    let mut hypanthium = false;
 
     for el in sp {
-        ov_vec.push(Ovary::from_str(el)?);
+        if el == "hyp" {
+            hypanthium = true;
+        } else {
+            ov_vec.push(Ovary::from_str(el)?);
+        }
     }
 
-    if ov_vec.len() > 1 {
-        Ok(Some(Ovary::Both))
-    } else {
-        Ok(Some(ov_vec.clone()[0]))
+    // more than one position means the position is variable
+    // in the group, which is not the same as half-inferior, so
+    // all of them are kept.
+    Ok((ov_vec, hypanthium))
+}
+
+// placentation, locules, ovules and the fusion of the carpels
+// only make sense on the carpels, so these are added after the fact.
+fn parse_carpel_details(
+    carpels: Option<FloralPart>,
+    record: &Record,
+) -> Result<Option<FloralPart>> {
+    let mut carpels = match carpels {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    let placentation = record.get("placentation");
+    let locules = record.get("locules");
+    let ovules = record.get("ovules");
+    let carpel_fusion = record.get("carpel_fusion");
+    let stigmas = record.get("stigmas");
+
+    if !(placentation.is_empty() || placentation == "-") {
+        let parsed: Result<Vec<Placentation>> = placentation
+            .split(';')
+            .map(Placentation::from_str)
+            .collect();
+        carpels.set_placentation(parsed?);
+    }
+    if !(locules.is_empty() || locules == "-") {
+        carpels.set_locules(Some(PartCount::from_str(locules)?));
+    }
+    if !(ovules.is_empty() || ovules == "-") {
+        carpels.set_ovules(Some(PartCount::from_str(ovules)?));
+    }
+    if !(carpel_fusion.is_empty() || carpel_fusion == "-") {
+        carpels.set_carpel_fusion(Some(CarpelFusion::from_str(carpel_fusion)?));
+    }
+    if !(stigmas.is_empty() || stigmas == "-") {
+        carpels.set_stigmas(Some(PartCount::from_str(stigmas)?));
+    }
+
+    Ok(Some(carpels))
+}
+
+// the arrangement and position of the stamens only make sense
+// on the stamens
+fn parse_stamen_details(
+    stamens: Option<FloralPart>,
+    record: &Record,
+) -> Result<Option<FloralPart>> {
+    let mut stamens = match stamens {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+
+    let stamen_arrangement = record.get("stamen_arrangement");
+    let stamen_position = record.get("stamen_position");
+
+    if !(stamen_arrangement.is_empty() || stamen_arrangement == "-") {
+        let parsed: Result<Vec<StamenArrangement>> = stamen_arrangement
+            .split(';')
+            .map(StamenArrangement::from_str)
+            .collect();
+        stamens.set_stamen_arrangement(parsed?);
+    }
+    if !(stamen_position.is_empty() || stamen_position == "-") {
+        stamens.set_stamen_position(Some(StamenPosition::from_str(stamen_position)?));
+    }
+
+    Ok(Some(stamens))
+}
+
+// the outer whorl is given as the part first, then
+// as any other floral part, e.g. EpK;3-5;c
+fn parse_outer_whorl(s: &str) -> Result<Option<FloralPart>> {
+    if s.is_empty() || s == "-" {
+        return Ok(None);
+    }
+    let (part, rest) = s.split_once(';').unwrap_or((s, ""));
+    let part = Part::from_str(part)?;
+    match part {
+        Part::Epicalyx | Part::Bracteoles => parse_floral_part_to_enum(rest, part, Vec::new()),
+        other => Err(Error::new(ErrorKind::ParseError(format!(
+            "the outer whorl must be an epicalyx (EpK) or bracteoles (Br), found {}",
+            other
+        )))),
     }
 }
 
+// parse the accessory structures
+fn parse_accessory(s: &str) -> Result<Vec<Accessory>> {
+    if s.is_empty() || s == "-" {
+        return Ok(vec![]);
+    }
+    s.split(';').map(Accessory::from_str).collect()
+}
+
+// parse the inflorescence(s)
+fn parse_inflorescence(s: &str) -> Result<Vec<Inflorescence>> {
+    if s.is_empty() || s == "-" {
+        return Ok(vec![]);
+    }
+    s.split(';').map(Inflorescence::from_str).collect()
+}
+
+// parse the seed characters
+fn parse_seed(s: &str) -> Result<Option<Seed>> {
+    if s.is_empty() || s == "-" {
+        return Ok(None);
+    }
+    Ok(Some(Seed::from_str(s)?))
+}
+
 // parse adnation
 fn parse_adnation(s: &str) -> Result<Adnation> {
     if s.is_empty() || s == "-" {
@@ -136,7 +330,7 @@ fn parse_adnation(s: &str) -> Result<Adnation> {
 fn parse_floral_part_to_enum(
     s: &str,
     floral_part: Part,
-    ovary: Option<Ovary>,
+    ovary: Vec<Ovary>,
 ) -> Result<Option<FloralPart>> {
     if s.is_empty() || s == "-" {
         return Ok(None);
@@ -179,41 +373,65 @@ fn parse_floral_part_to_enum(
     // e.g. 2-4;f;v
     // this is the 2-4 bit
     for el in sp {
+        // a whorl may end in p (petaloid) or k (sepaloid)
+        let (el, nature) = if let Some(e) = el.strip_suffix('p') {
+            (e, Some(WhorlNature::Petaloid))
+        } else if let Some(e) = el.strip_suffix('k') {
+            (e, Some(WhorlNature::Sepaloid))
+        } else {
+            (el, None)
+        };
         // we got a range
         if el.contains('-') {
             let split = el.split('-').collect::<Vec<&str>>();
 
             let (updated_split, (sterile, connate, variable)) = any_contains_vars(split);
 
-            floral.add_whorl(Whorl::new(
+            let mut whorl = Whorl::new(
                 None,
                 Some(FloralPartNumber::from_str(&updated_split[0])?),
                 Some(FloralPartNumber::from_str(&updated_split[1])?),
                 sterile,
                 connate,
                 variable,
-            ));
+            );
+            whorl.set_nature(nature);
+            floral.add_whorl(whorl);
         } else if el == "c" {
             // c == connate
             floral.set_connation(true);
         } else if el == "v" {
             // v == variable
             floral.set_connation_variation(true);
+        } else if let Ok(organ) = ConnateOrgan::from_str(el) {
+            // e.g. cf == filaments connate
+            if organ.part() != floral.get_part() {
+                return Err(Error::new(ErrorKind::ParseError(format!(
+                    "{} are part of {}, not {}",
+                    organ,
+                    organ.part(),
+                    floral.get_part()
+                ))));
+            }
+            floral.add_connate_organ(organ);
         } else {
             // it's just a plain number
             let el_single_vec = vec![el];
             let (updated_vec, (sterile, connate, variable)) = any_contains_vars(el_single_vec);
 
-            floral.add_whorl(Whorl::new(
+            let mut whorl = Whorl::new(
                 Some(FloralPartNumber::from_str(&updated_vec[0])?),
                 None,
                 None,
                 sterile,
                 connate,
                 variable,
-            ));
+            );
+            whorl.set_nature(nature);
+            floral.add_whorl(whorl);
         }
     }
+    floral.position_whorls();
 
     Ok(Some(floral))
 }
@@ -0,0 +1,547 @@
+use crate::analysis::count_parts;
+use crate::error::{Error, ErrorKind};
+use crate::explain::ExplainFloralFormula;
+use crate::floral::{FloralPart, FloralPartNumber, Formula, Fruit, Ovary};
+use crate::i18n::{t, Locale};
+use crate::output::{OutputFormat, Table, Value};
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// A small random number generator (SplitMix64). It is not fit for
+/// anything secret, but the same seed always gives the same quiz.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Constructor for the [`Rng`] struct, from a seed
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    /// The next random number
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// A random number below `n`, which must not be zero
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    /// Shuffle a slice in place
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.below(idx + 1));
+        }
+    }
+}
+
+/// The kinds of question which can be asked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestionKind {
+    /// Which family a formula belongs to
+    Family,
+    /// The ovary position of a family
+    Ovary,
+    /// A fruit of a family
+    Fruit,
+    /// What a floral part in a formula means
+    Part,
+}
+
+impl QuestionKind {
+    /// All of the kinds of question
+    pub const ALL: [QuestionKind; 4] = [
+        QuestionKind::Family,
+        QuestionKind::Ovary,
+        QuestionKind::Fruit,
+        QuestionKind::Part,
+    ];
+}
+
+impl Display for QuestionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuestionKind::Family => write!(f, "family"),
+            QuestionKind::Ovary => write!(f, "ovary"),
+            QuestionKind::Fruit => write!(f, "fruit"),
+            QuestionKind::Part => write!(f, "part"),
+        }
+    }
+}
+
+impl FromStr for QuestionKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "family" => Ok(Self::Family),
+            "ovary" => Ok(Self::Ovary),
+            "fruit" => Ok(Self::Fruit),
+            "part" => Ok(Self::Part),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "question type: {}, not recognised (try family, ovary, fruit or part)",
+                other
+            )))),
+        }
+    }
+}
+
+/// A multiple choice question.
+#[derive(Debug, Clone)]
+pub struct Question {
+    kind: QuestionKind,
+    prompt: String,
+    choices: Vec<String>,
+    answer: usize,
+}
+
+impl Question {
+    /// Get the kind of question
+    pub fn get_kind(&self) -> QuestionKind {
+        self.kind
+    }
+    /// Get the question itself
+    pub fn get_prompt(&self) -> &str {
+        &self.prompt
+    }
+    /// Get the choices
+    pub fn get_choices(&self) -> &Vec<String> {
+        &self.choices
+    }
+    /// Get the index of the right choice
+    pub fn get_answer(&self) -> usize {
+        self.answer
+    }
+    /// Whether a choice is the right one
+    pub fn is_correct(&self, choice: usize) -> bool {
+        choice == self.answer
+    }
+}
+
+/// The letter a choice is given by, e.g. `b` for the second.
+pub fn choice_letter(idx: usize) -> char {
+    (b'a' + idx as u8) as char
+}
+
+// the number of wrong choices to give alongside the right one
+const DISTRACTORS: usize = 3;
+
+// shuffle the right answer in among the wrong ones
+fn question(
+    kind: QuestionKind,
+    prompt: String,
+    answer: String,
+    mut wrong: Vec<String>,
+    rng: &mut Rng,
+) -> Option<Question> {
+    wrong.retain(|e| *e != answer);
+    if wrong.is_empty() {
+        return None;
+    }
+    wrong.truncate(DISTRACTORS);
+    let mut choices = wrong;
+    choices.push(answer.clone());
+    rng.shuffle(&mut choices);
+    let answer = choices.iter().position(|e| *e == answer)?;
+    Some(Question {
+        kind,
+        prompt,
+        choices,
+        answer,
+    })
+}
+
+fn capitalise(s: &str) -> String {
+    let mut c = s.chars();
+    match c.next() {
+        None => String::new(),
+        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+    }
+}
+
+// the first line of a formula, without the adnation beneath
+fn formula_line(formula: &Formula) -> String {
+    formula
+        .to_string()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+// what a floral part means, without its symbol, on one line
+fn part_meaning(part: &FloralPart, locale: Locale) -> String {
+    let explained = part.explain_in(locale);
+    explained
+        .lines()
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+        .enumerate()
+        .map(|(idx, line)| match line.split_once(" = ") {
+            Some((_, meaning)) if idx == 0 => meaning,
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Make up to `number` questions about the flowers in `pool`, given with
+/// the order and family of each. The wrong choices are drawn from `all`,
+/// which is usually the whole database. Fewer questions are made if the
+/// pool runs out of different ones.
+pub fn generate(
+    pool: &[(&str, &str, &Formula)],
+    all: &[(&str, &str, &Formula)],
+    kinds: &[QuestionKind],
+    number: usize,
+    locale: Locale,
+    rng: &mut Rng,
+) -> Vec<Question> {
+    let mut questions: Vec<Question> = Vec::new();
+    if pool.is_empty() || kinds.is_empty() {
+        return questions;
+    }
+    // some subjects can't be asked about, so give up after a while
+    let mut attempts = number * 50;
+    while questions.len() < number && attempts > 0 {
+        attempts -= 1;
+        let kind = kinds[rng.below(kinds.len())];
+        let subject = pool[rng.below(pool.len())];
+        let made = match kind {
+            QuestionKind::Family => family_question(subject, all, locale, rng),
+            QuestionKind::Ovary => ovary_question(subject, all, locale, rng),
+            QuestionKind::Fruit => fruit_question(subject, all, locale, rng),
+            QuestionKind::Part => part_question(subject, all, locale, rng),
+        };
+        if let Some(q) = made {
+            if questions.iter().all(|e| e.prompt != q.prompt) {
+                questions.push(q);
+            }
+        }
+    }
+    questions
+}
+
+// show a formula and ask for the family, with wrong choices from the
+// same order first, then from families with a similar formula
+fn family_question(
+    (order, family, formula): (&str, &str, &Formula),
+    all: &[(&str, &str, &Formula)],
+    locale: Locale,
+    rng: &mut Rng,
+) -> Option<Question> {
+    let line = formula_line(formula);
+    // families with the same formula would be right too
+    let same: BTreeSet<&str> = all
+        .iter()
+        .filter(|(_, _, f)| formula_line(f) == line)
+        .map(|(_, fam, _)| *fam)
+        .collect();
+    let mut groups: [Vec<&str>; 3] = Default::default();
+    for (o, fam, f) in all {
+        if same.contains(fam) || groups.iter().any(|e| e.contains(fam)) {
+            continue;
+        }
+        let group = if *o == order {
+            0
+        } else if f.merosity() == formula.merosity() && f.androecium() == formula.androecium() {
+            1
+        } else {
+            2
+        };
+        groups[group].push(fam);
+    }
+    let mut wrong = Vec::new();
+    for mut group in groups {
+        rng.shuffle(&mut group);
+        wrong.extend(group.into_iter().map(capitalise));
+    }
+    question(
+        QuestionKind::Family,
+        t!(locale, "Which family has the floral formula {}?", line),
+        capitalise(family),
+        wrong,
+        rng,
+    )
+}
+
+// all the flowers of a family, as some families have several
+fn family_formulae<'a>(family: &str, all: &[(&str, &str, &'a Formula)]) -> Vec<&'a Formula> {
+    all.iter()
+        .filter(|(_, fam, _)| *fam == family)
+        .map(|(_, _, f)| *f)
+        .collect()
+}
+
+// ask for the ovary position of a family, if all of its flowers agree;
+// a position which varies in the family is an answer of its own
+fn ovary_question(
+    (_, family, _): (&str, &str, &Formula),
+    all: &[(&str, &str, &Formula)],
+    locale: Locale,
+    rng: &mut Rng,
+) -> Option<Question> {
+    let describe = |positions: &[Ovary]| match positions {
+        [ovary] => ovary.explain_in(locale),
+        _ => t!(locale, "an ovary position which varies in the group"),
+    };
+    let positions: BTreeSet<_> = family_formulae(family, all)
+        .iter()
+        .filter_map(|f| f.get_carpels().as_ref())
+        .filter(|e| !e.get_ovary().is_empty())
+        .map(|e| describe(e.get_ovary()))
+        .collect();
+    if positions.len() != 1 {
+        return None;
+    }
+    let mut wrong: Vec<_> = [
+        &[Ovary::Superior][..],
+        &[Ovary::HalfInferior],
+        &[Ovary::Inferior],
+        &[Ovary::Superior, Ovary::Inferior],
+    ]
+    .into_iter()
+    .map(describe)
+    .collect();
+    rng.shuffle(&mut wrong);
+    question(
+        QuestionKind::Ovary,
+        t!(
+            locale,
+            "What is the ovary position in {}?",
+            capitalise(family)
+        ),
+        positions.into_iter().next()?,
+        wrong,
+        rng,
+    )
+}
+
+// ask for one of the fruits of a family, alongside fruits it doesn't have
+fn fruit_question(
+    (_, family, _): (&str, &str, &Formula),
+    all: &[(&str, &str, &Formula)],
+    locale: Locale,
+    rng: &mut Rng,
+) -> Option<Question> {
+    let fruits = |formulae: &[&Formula]| -> Vec<Fruit> {
+        let mut fruits = Vec::new();
+        for fruit in formulae.iter().flat_map(|f| f.get_fruit()) {
+            if fruit.get_fruit() != Fruit::None && !fruits.contains(&fruit.get_fruit()) {
+                fruits.push(fruit.get_fruit());
+            }
+        }
+        fruits
+    };
+    let own = fruits(&family_formulae(family, all));
+    if own.is_empty() {
+        return None;
+    }
+    let every: Vec<_> = all.iter().map(|(_, _, f)| *f).collect();
+    let mut wrong: Vec<_> = fruits(&every)
+        .into_iter()
+        .filter(|e| !own.contains(e))
+        .map(|e| t!(locale, &e.to_string()))
+        .collect();
+    rng.shuffle(&mut wrong);
+    let answer = own[rng.below(own.len())];
+    question(
+        QuestionKind::Fruit,
+        t!(
+            locale,
+            "Which of these is a fruit of {}?",
+            capitalise(family)
+        ),
+        t!(locale, &answer.to_string()),
+        wrong,
+        rng,
+    )
+}
+
+// ask what one of the floral parts of a formula means, alongside
+// the meanings of the same kind of part in other formulae
+fn part_question(
+    (_, _, formula): (&str, &str, &Formula),
+    all: &[(&str, &str, &Formula)],
+    locale: Locale,
+    rng: &mut Rng,
+) -> Option<Question> {
+    let parts = |f: &Formula| -> Vec<FloralPart> {
+        [
+            f.get_tepals(),
+            f.get_sepals(),
+            f.get_petals(),
+            f.get_stamens(),
+            f.get_carpels(),
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    };
+    // there's little to say about a part which isn't there, e.g. G0
+    let own: Vec<_> = parts(formula)
+        .into_iter()
+        .filter(|e| !matches!(count_parts(e), Some((_, FloralPartNumber::Finite(0)))))
+        .collect();
+    if own.is_empty() {
+        return None;
+    }
+    let part = &own[rng.below(own.len())];
+    let symbol = part.to_string();
+    let mut wrong: Vec<_> = all
+        .iter()
+        .flat_map(|(_, _, f)| parts(f))
+        .filter(|e| e.get_part() == part.get_part() && e.to_string() != symbol)
+        .map(|e| part_meaning(&e, locale))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    rng.shuffle(&mut wrong);
+    question(
+        QuestionKind::Part,
+        t!(locale, "What does {} mean in a floral formula?", symbol),
+        part_meaning(part, locale),
+        wrong,
+        rng,
+    )
+}
+
+/// The questions to hand out, numbered, with their choices lettered.
+/// Text and Markdown are laid out to be read, and the other formats
+/// are a table of the questions, with the answers, for other programs.
+pub fn question_sheet(
+    questions: &[Question],
+    seed: u64,
+    locale: Locale,
+    format: OutputFormat,
+) -> String {
+    if format.is_tabular() {
+        let mut table = Table::new(&["number", "type", "question", "choices", "answer"]);
+        for (idx, q) in questions.iter().enumerate() {
+            table.push_row(vec![
+                Value::from(idx + 1),
+                q.kind.to_string().into(),
+                q.prompt.as_str().into(),
+                q.choices.join(" | ").into(),
+                q.choices[q.answer].as_str().into(),
+            ]);
+        }
+        return table.render(format);
+    }
+    let title = t!(locale, "Floral formula quiz (seed {})", seed);
+    let mut out = match format {
+        OutputFormat::Markdown => format!("## {}\n", title),
+        _ => format!("{}\n", title),
+    };
+    for (idx, q) in questions.iter().enumerate() {
+        out += &format!("\n{}. {}\n", idx + 1, q.prompt);
+        for (choice_idx, choice) in q.choices.iter().enumerate() {
+            let letter = choice_letter(choice_idx);
+            out += &match format {
+                OutputFormat::Markdown => format!("    - {}) {}\n", letter, choice),
+                _ => format!("   {}) {}\n", letter, choice),
+            };
+        }
+    }
+    out
+}
+
+/// The answers to the questions on a [`question_sheet`].
+pub fn answer_key(
+    questions: &[Question],
+    seed: u64,
+    locale: Locale,
+    format: OutputFormat,
+) -> String {
+    let title = t!(locale, "Answers (seed {})", seed);
+    let mut out = match format {
+        OutputFormat::Markdown => format!("## {}\n\n", title),
+        _ => format!("{}\n\n", title),
+    };
+    for (idx, q) in questions.iter().enumerate() {
+        out += &format!(
+            "{}. {}) {}\n",
+            idx + 1,
+            choice_letter(q.answer),
+            q.choices[q.answer]
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_quiz() {
+        // the same seed gives the same numbers
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        assert!((0..10).all(|_| a.next_u64() == b.next_u64()));
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+        let mut shuffled: Vec<_> = (0..20).collect();
+        a.shuffle(&mut shuffled);
+        assert_ne!(shuffled, (0..20).collect::<Vec<_>>());
+        shuffled.sort();
+        assert_eq!(shuffled, (0..20).collect::<Vec<_>>());
+
+        let data = crate::parse::parse_data().unwrap();
+        let all: Vec<_> = data
+            .iter()
+            .map(|((order, family, _), formula)| (*order, *family, formula))
+            .collect();
+        let quiz = |seed| {
+            generate(
+                &all,
+                &all,
+                &QuestionKind::ALL,
+                12,
+                Locale::En,
+                &mut Rng::new(seed),
+            )
+        };
+        let questions = quiz(7);
+        assert_eq!(questions.len(), 12);
+        for q in &questions {
+            assert!(q.get_choices().len() >= 2 && q.get_choices().len() <= 4);
+            assert!(q.is_correct(q.get_answer()));
+            // no choice is given twice
+            let mut choices = q.get_choices().clone();
+            choices.sort();
+            choices.dedup();
+            assert_eq!(choices.len(), q.get_choices().len());
+        }
+        let family = questions
+            .iter()
+            .find(|e| e.get_kind() == QuestionKind::Family)
+            .unwrap();
+        // the formula asked about is one of the answer's
+        let answer = &family.get_choices()[family.get_answer()];
+        assert!(data.iter().any(|((_, f, _), formula)| {
+            f.eq_ignore_ascii_case(answer)
+                && family
+                    .get_prompt()
+                    .contains(formula.to_string().lines().next().unwrap())
+        }));
+
+        // the same seed sets the same quiz
+        let sheet = question_sheet(&questions, 7, Locale::En, OutputFormat::Text);
+        assert_eq!(
+            sheet,
+            question_sheet(&quiz(7), 7, Locale::En, OutputFormat::Text)
+        );
+        assert_ne!(
+            sheet,
+            question_sheet(&quiz(8), 8, Locale::En, OutputFormat::Text)
+        );
+        assert!(sheet.starts_with("Floral formula quiz (seed 7)\n\n1. "));
+        let key = answer_key(&questions, 7, Locale::En, OutputFormat::Text);
+        assert_eq!(key.lines().count(), 2 + questions.len());
+    }
+}
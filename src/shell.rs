@@ -0,0 +1,663 @@
+use crate::cli::{self, Data};
+use crate::completions;
+use crate::error::{Error, ErrorKind, Result};
+use crate::explain::Verbosity;
+use crate::glossary;
+use crate::i18n::Locale;
+use crate::notation::is_combining;
+use crate::output::OutputFormat;
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+// the most lines of history to keep
+const HISTORY_LENGTH: usize = 500;
+
+const SHELL_HELP: &str = "\
+Commands:
+
+  show <NAME>...          Print the formulae of families (or orders)
+  list [orders|families]  Print every formula, or list the orders or families
+  search <QUERY>          Print the formulae of families matching QUERY
+  compare <NAME>...       Compare two or more families side by side
+  explain <NAME|FORMULA>  Explain a family's formula, or one typed out
+  export, stats, quiz     As on the command line
+  glossary [TERM]         Define a term or a symbol
+  set <SETTING> <VALUE>   Change a setting, for the rest of the session:
+                            format text|markdown|tsv|csv|json
+                            lang en|es|de|fr
+                            explain off|brief|normal|full
+                            order on|off
+  set                     Show the settings
+  history                 Show the commands typed so far
+  help                    Show this help
+  quit                    Leave the shell (or press Ctrl-D)
+
+The flags of the command line work too, e.g. show -e liliaceae. Press
+tab to complete a command, or a family or order name.
+";
+
+/// Start the shell, which reads commands until it is told to quit.
+/// The database (`source`) is parsed once, at the start, and the flags given
+/// with `floral shell` are the settings to begin with.
+pub fn run(args: Vec<OsString>, source: &str) -> Result<()> {
+    let mut pargs = pico_args::Arguments::from_vec(args);
+    let mut settings = Settings {
+        format: pargs.opt_value_from_fn("--format", OutputFormat::from_str)?,
+        locale: pargs
+            .opt_value_from_fn(["-l", "--lang"], Locale::from_str)?
+            .unwrap_or_default(),
+        explain: pargs
+            .contains(["-e", "--explain"])
+            .then_some(Verbosity::Normal),
+        order: pargs.contains(["-o", "--order"]),
+    };
+    let data = crate::parse::parse_data_from(source)?;
+    let completer = Completer::new(&data);
+    let mut editor = LineEditor::new(history_path());
+
+    println!("floral shell, type help for the commands, or quit to leave");
+    loop {
+        let order = settings.order;
+        let line = match editor.read_line("floral> ", |e| completer.complete(e, order)) {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                return Err(Error::new(ErrorKind::GenericCli(format!(
+                    "couldn't read from the terminal: {e}"
+                ))))
+            }
+        };
+        let words = split_words(&line);
+        let result = match words.first().map(|e| e.as_str()) {
+            None => Ok(()),
+            Some("quit" | "exit") => break,
+            Some("help") => {
+                print!("{}", SHELL_HELP);
+                Ok(())
+            }
+            Some("history") => {
+                for (idx, line) in editor.history.iter().enumerate() {
+                    println!("{:>4}  {}", idx + 1, line);
+                }
+                Ok(())
+            }
+            Some("set") => settings.set(&words[1..]),
+            Some("shell") => Err(Error::new(ErrorKind::GenericCli(
+                "this is the shell already".into(),
+            ))),
+            Some(_) => {
+                let args = settings.args(&words);
+                cli::run(args.into_iter().map(OsString::from).collect(), &data)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("floral error: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// the settings which are added to each command
+struct Settings {
+    format: Option<OutputFormat>,
+    locale: Locale,
+    explain: Option<Verbosity>,
+    order: bool,
+}
+
+impl Settings {
+    // e.g. `set format json`
+    fn set(&mut self, words: &[String]) -> Result<()> {
+        let on_off = |value: &str| match value {
+            "on" | "yes" | "true" => Ok(true),
+            "off" | "no" | "false" => Ok(false),
+            other => Err(Error::new(ErrorKind::FromStr(format!(
+                "setting: {}, not recognised (try on or off)",
+                other
+            )))),
+        };
+        match (words.first().map(|e| e.as_str()), words.get(1)) {
+            (None, _) => {
+                let format = self.format.map(|e| e.to_string());
+                let explain = self.explain.map(|e| e.to_string());
+                println!("format   {}", format.as_deref().unwrap_or("default"));
+                println!("lang     {}", self.locale);
+                println!("explain  {}", explain.as_deref().unwrap_or("off"));
+                println!("order    {}", if self.order { "on" } else { "off" });
+            }
+            (Some("format"), Some(value)) => self.format = Some(OutputFormat::from_str(value)?),
+            (Some("lang"), Some(value)) => self.locale = Locale::from_str(value)?,
+            (Some("explain"), Some(value)) if on_off(value).ok() == Some(false) => {
+                self.explain = None
+            }
+            (Some("explain"), Some(value)) if on_off(value).ok() == Some(true) => {
+                self.explain = Some(Verbosity::Normal)
+            }
+            (Some("explain"), Some(value)) => self.explain = Some(Verbosity::from_str(value)?),
+            (Some("order"), Some(value)) => self.order = on_off(value)?,
+            (Some(setting), _) => {
+                return Err(Error::new(ErrorKind::GenericCli(format!(
+                    "can't set {setting}, try set format, lang, explain or order and a value"
+                ))))
+            }
+        }
+        Ok(())
+    }
+
+    // the words of a command, with the settings added unless
+    // the command gives its own
+    fn args(&self, words: &[String]) -> Vec<String> {
+        let args = words.to_vec();
+        let has = |flags: &[&str]| {
+            words.iter().any(|w| {
+                flags
+                    .iter()
+                    .any(|f| w == f || w.starts_with(&format!("{f}=")))
+            })
+        };
+
+        self.add_settings(args, has)
+    }
+
+    fn add_settings(&self, mut args: Vec<String>, has: impl Fn(&[&str]) -> bool) -> Vec<String> {
+        let command = args[0].clone();
+        if let Some(format) = self.format {
+            if !has(&["--format"]) {
+                args.extend(["--format".into(), format.to_string()]);
+            }
+        }
+        // the glossary is only in English
+        if command == "glossary" {
+            return args;
+        }
+        if !has(&["-l", "--lang"]) {
+            args.extend(["-l".into(), self.locale.to_string()]);
+        }
+        if let Some(level) = self.explain {
+            if !has(&["-e", "--explain"]) {
+                args.push(format!("--explain={}", level));
+            }
+        }
+        if self.order && command != "explain" && !has(&["-o", "--order"]) {
+            args.push("-o".into());
+        }
+        args
+    }
+}
+
+/// Split a line into words at the spaces, keeping anything in
+/// single or double quotes together, e.g. a formula with spaces.
+pub fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// The words which the shell can complete, taken from the database.
+pub struct Completer {
+    families: Vec<String>,
+    orders: Vec<String>,
+}
+
+impl Completer {
+    /// Constructor for the [`Completer`] struct
+    pub fn new(data: &Data) -> Self {
+        Self {
+            families: completions::names("families", data).unwrap_or_default(),
+            orders: completions::names("orders", data).unwrap_or_default(),
+        }
+    }
+
+    /// The words which could finish the last word of `line` (the
+    /// line up to the cursor), and the byte at which that word
+    /// starts. Orders are offered rather than families with `order`.
+    pub fn complete(&self, line: &str, order: bool) -> (usize, Vec<String>) {
+        let start = line
+            .char_indices()
+            .rfind(|(_, c)| c.is_whitespace())
+            .map_or(0, |(idx, c)| idx + c.len_utf8());
+        let word = &line[start..];
+        let before: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let owned = |words: &[&str]| words.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        let candidates: Vec<String> = match before.as_slice() {
+            [] => owned(&SHELL_COMMANDS),
+            // the database is the one the shell was started with
+            _ if word.starts_with('-') => completions::FLAGS
+                .iter()
+                .filter(|e| e.get_long() != "db")
+                .flat_map(|e| e.forms())
+                .collect(),
+            ["set"] => owned(&["format", "lang", "explain", "order"]),
+            ["set", "format"] => owned(&["text", "markdown", "tsv", "csv", "json"]),
+            ["set", "lang"] => owned(&["en", "es", "de", "fr"]),
+            ["set", "explain"] => owned(&["off", "brief", "normal", "full"]),
+            ["set", "order"] => owned(&["on", "off"]),
+            ["set", ..] => Vec::new(),
+            ["list"] => owned(&["orders", "families"]),
+            ["glossary", ..] => glossary::entries()
+                .iter()
+                .map(|e| e.get_term().to_string())
+                .collect(),
+            words if order || words.iter().any(|e| *e == "-o" || *e == "--order") => {
+                self.orders.clone()
+            }
+            _ => self.families.clone(),
+        };
+        let lower = word.to_lowercase();
+        let matches = candidates
+            .into_iter()
+            .filter(|e| e.to_lowercase().starts_with(&lower))
+            .collect();
+        (start, matches)
+    }
+}
+
+// the commands which the shell knows, for completion
+const SHELL_COMMANDS: [&str; 14] = [
+    "show", "list", "search", "compare", "explain", "export", "stats", "quiz", "glossary", "set",
+    "history", "help", "quit", "exit",
+];
+
+// the history file, in the home directory
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".floral_history"))
+}
+
+// puts the terminal into raw mode with `stty`, so keys can be read one at
+// a time, and back again when dropped
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enter() -> Option<Self> {
+        let saved = Command::new("stty")
+            .arg("-g")
+            .stdin(Stdio::inherit())
+            .output()
+            .ok()?;
+        if !saved.status.success() {
+            return None;
+        }
+        let saved = String::from_utf8(saved.stdout).ok()?.trim().to_string();
+        let raw = Command::new("stty")
+            .args(["-icanon", "-echo", "-isig", "-ixon", "min", "1"])
+            .stdin(Stdio::inherit())
+            .status()
+            .ok()?;
+        raw.success().then_some(Self { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty")
+            .arg(&self.saved)
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+// the keys the line editor knows about
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Tab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    Cancel,
+    ClearLine,
+    EndOfFile,
+    Unknown,
+}
+
+// read a key from the terminal, in raw mode
+fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let mut byte = [0u8; 1];
+    let mut next = |input: &mut dyn Read| -> io::Result<Option<u8>> {
+        Ok((input.read(&mut byte)? == 1).then_some(byte[0]))
+    };
+    let first = match next(input)? {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    let key = match first {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        b'\t' => Key::Tab,
+        0x01 => Key::Home,
+        0x03 => Key::Cancel,
+        0x04 => Key::EndOfFile,
+        0x05 => Key::End,
+        0x15 => Key::ClearLine,
+        0x1b => match next(input)? {
+            Some(b'[') | Some(b'O') => match next(input)? {
+                Some(b'A') => Key::Up,
+                Some(b'B') => Key::Down,
+                Some(b'C') => Key::Right,
+                Some(b'D') => Key::Left,
+                Some(b'H') => Key::Home,
+                Some(b'F') => Key::End,
+                Some(b'3') => {
+                    next(input)?; // the ~
+                    Key::Delete
+                }
+                _ => Key::Unknown,
+            },
+            _ => Key::Unknown,
+        },
+        b if b < 0x20 => Key::Unknown,
+        b => {
+            // the rest of a multibyte character
+            let mut bytes = vec![b];
+            let len = match b {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            while bytes.len() < len {
+                match next(input)? {
+                    Some(b) => bytes.push(b),
+                    None => break,
+                }
+            }
+            match std::str::from_utf8(&bytes)
+                .ok()
+                .and_then(|e| e.chars().next())
+            {
+                Some(c) => Key::Char(c),
+                None => Key::Unknown,
+            }
+        }
+    };
+    Ok(Some(key))
+}
+
+// the number of columns some characters take up in the terminal
+fn width(chars: &[char]) -> usize {
+    chars.iter().filter(|e| !is_combining(**e)).count()
+}
+
+// a line editor with history and tab completion
+struct LineEditor {
+    history: Vec<String>,
+    history_path: Option<PathBuf>,
+}
+
+impl LineEditor {
+    fn new(history_path: Option<PathBuf>) -> Self {
+        let mut history: Vec<String> = history_path
+            .as_ref()
+            .and_then(|e| std::fs::read_to_string(e).ok())
+            .map(|e| e.lines().map(|l| l.to_string()).collect())
+            .unwrap_or_default();
+        // trim the file too, so that it doesn't grow forever
+        if history.len() > HISTORY_LENGTH {
+            history.drain(..history.len() - HISTORY_LENGTH);
+            if let Some(path) = &history_path {
+                let _ = std::fs::write(path, history.join("\n") + "\n");
+            }
+        }
+        Self {
+            history,
+            history_path,
+        }
+    }
+
+    // remember a line, in the history file too if there is one; it
+    // doesn't matter much if that can't be written to
+    fn add_history(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || self.history.last().map(|e| e.as_str()) == Some(line) {
+            return;
+        }
+        self.history.push(line.to_string());
+        if self.history.len() > HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+        if let Some(path) = &self.history_path {
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+    }
+
+    // read a line, or `None` at the end of the input
+    fn read_line(
+        &mut self,
+        prompt: &str,
+        complete: impl Fn(&str) -> (usize, Vec<String>),
+    ) -> io::Result<Option<String>> {
+        let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+        let raw = if interactive { RawMode::enter() } else { None };
+        let line = match raw {
+            Some(_) => self.edit_line(prompt, complete)?,
+            None => {
+                // no terminal to edit in, so read the lines as they come
+                if interactive {
+                    print!("{}", prompt);
+                    io::stdout().flush()?;
+                }
+                let mut line = String::new();
+                match io::stdin().lock().read_line(&mut line)? {
+                    0 => None,
+                    _ => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+                }
+            }
+        };
+        drop(raw);
+        // lines piped in aren't worth remembering
+        if let Some(line) = line.as_ref().filter(|_| interactive) {
+            self.add_history(line);
+        }
+        Ok(line)
+    }
+
+    fn edit_line(
+        &mut self,
+        prompt: &str,
+        complete: impl Fn(&str) -> (usize, Vec<String>),
+    ) -> io::Result<Option<String>> {
+        let mut stdin = io::stdin().lock();
+        let mut line: Vec<char> = Vec::new();
+        let mut cursor = 0;
+        // where we are in the history, and the line typed before going there
+        let mut history_idx = self.history.len();
+        let mut draft: Vec<char> = Vec::new();
+
+        let redraw = |line: &[char], cursor: usize| -> io::Result<()> {
+            let mut out = io::stdout().lock();
+            write!(out, "\r\x1b[K{}{}", prompt, line.iter().collect::<String>())?;
+            let back = width(&line[cursor..]);
+            if back > 0 {
+                write!(out, "\x1b[{}D", back)?;
+            }
+            out.flush()
+        };
+        redraw(&line, cursor)?;
+
+        loop {
+            let key = match read_key(&mut stdin)? {
+                Some(key) => key,
+                None => return Ok(None),
+            };
+            match key {
+                Key::Enter => {
+                    println!();
+                    return Ok(Some(line.into_iter().collect()));
+                }
+                Key::Char(c) => {
+                    line.insert(cursor, c);
+                    cursor += 1;
+                }
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    line.remove(cursor);
+                }
+                Key::Delete if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::EndOfFile if line.is_empty() => {
+                    println!();
+                    return Ok(None);
+                }
+                Key::EndOfFile if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::Cancel => {
+                    println!("^C");
+                    line.clear();
+                    cursor = 0;
+                }
+                Key::ClearLine => {
+                    line.drain(..cursor);
+                    cursor = 0;
+                }
+                Key::Left if cursor > 0 => cursor -= 1,
+                Key::Right if cursor < line.len() => cursor += 1,
+                Key::Home => cursor = 0,
+                Key::End => cursor = line.len(),
+                Key::Up if history_idx > 0 => {
+                    if history_idx == self.history.len() {
+                        draft = line.clone();
+                    }
+                    history_idx -= 1;
+                    line = self.history[history_idx].chars().collect();
+                    cursor = line.len();
+                }
+                Key::Down if history_idx < self.history.len() => {
+                    history_idx += 1;
+                    line = match self.history.get(history_idx) {
+                        Some(e) => e.chars().collect(),
+                        None => draft.clone(),
+                    };
+                    cursor = line.len();
+                }
+                Key::Tab => {
+                    let before: String = line[..cursor].iter().collect();
+                    let (start, candidates) = complete(&before);
+                    let start = before[..start].chars().count();
+                    let word = &line[start..cursor];
+                    let replacement = match candidates.as_slice() {
+                        [] => None,
+                        [only] => Some(format!("{} ", only)),
+                        [first, rest @ ..] => {
+                            // as far as all of the candidates agree
+                            let mut prefix: Vec<char> = first.chars().collect();
+                            for other in rest {
+                                let agree = prefix
+                                    .iter()
+                                    .zip(other.chars())
+                                    .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                                    .count();
+                                prefix.truncate(agree);
+                            }
+                            if prefix.len() > word.len() {
+                                Some(prefix.into_iter().collect())
+                            } else {
+                                println!();
+                                let options = textwrap::Options::new(78);
+                                println!("{}", textwrap::fill(&candidates.join("  "), options));
+                                None
+                            }
+                        }
+                    };
+                    match replacement {
+                        Some(replacement) => {
+                            let replacement: Vec<char> = replacement.chars().collect();
+                            cursor = start + replacement.len();
+                            line.splice(start..start + word.len(), replacement);
+                        }
+                        None if candidates.is_empty() => print!("\x07"),
+                        None => (),
+                    }
+                }
+                _ => print!("\x07"),
+            }
+            redraw(&line, cursor)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_words_in_quotes() {
+        assert_eq!(
+            split_words("explain '*,K(5), C(5)'  \"capsule\" ''"),
+            vec!["explain", "*,K(5), C(5)", "capsule", ""]
+        );
+        assert!(split_words("   ").is_empty());
+    }
+
+    #[test]
+    fn complete_commands_names_and_flags() {
+        let data = crate::parse::parse_data().unwrap();
+        let completer = Completer::new(&data);
+        assert_eq!(
+            completer.complete("sh", false),
+            (0, vec!["show".to_string()])
+        );
+        assert_eq!(
+            completer.complete("show li", false),
+            (5, vec!["liliaceae".to_string(), "linaceae".to_string()])
+        );
+        assert_eq!(
+            completer.complete("show LIL", true),
+            (5, vec!["Liliales".to_string()])
+        );
+        assert_eq!(
+            completer.complete("compare -o lil", false),
+            (11, vec!["Liliales".to_string()])
+        );
+        assert_eq!(
+            completer.complete("set format j", false),
+            (11, vec!["json".to_string()])
+        );
+        assert_eq!(
+            completer.complete("show --form", false),
+            (5, vec!["--format".to_string()])
+        );
+        assert_eq!(completer.complete("list f", false).1, vec!["families"]);
+        assert!(completer.complete("show zzz", false).1.is_empty());
+    }
+}
@@ -0,0 +1,382 @@
+use crate::analysis::count_parts;
+use crate::floral::{FloralPartNumber, Formula, Fruit, Ovary, Symmetry};
+use crate::output::{Table, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Statistics over the whole database, or a part of it. Each family
+/// is counted once, however many flower types it has, but under every
+/// feature of its flowers, so a family with both superior and inferior
+/// ovaries is counted under both.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    orders: usize,
+    families: usize,
+    entries: usize,
+    symmetry: Vec<(String, usize)>,
+    merosity: Vec<(String, usize)>,
+    ovary: Vec<(String, usize)>,
+    fruit: Vec<(String, usize)>,
+    connate_petals: usize,
+    adnation: usize,
+    infinite_stamens: usize,
+    per_order: Vec<OrderStats>,
+}
+
+/// The statistics of a single order.
+#[derive(Debug, Clone, Default)]
+pub struct OrderStats {
+    order: String,
+    families: usize,
+    entries: usize,
+    radial: usize,
+    bilateral: usize,
+    superior: usize,
+    half_inferior: usize,
+    inferior: usize,
+    connate_petals: usize,
+    adnation: usize,
+    infinite_stamens: usize,
+}
+
+impl OrderStats {
+    /// Get the name of the order
+    pub fn get_order(&self) -> &str {
+        &self.order
+    }
+    /// Get the number of families
+    pub fn get_families(&self) -> usize {
+        self.families
+    }
+    /// Get the number of flower types
+    pub fn get_entries(&self) -> usize {
+        self.entries
+    }
+    /// Get the number of families with radial flowers
+    pub fn get_radial(&self) -> usize {
+        self.radial
+    }
+    /// Get the number of families with bilateral flowers
+    pub fn get_bilateral(&self) -> usize {
+        self.bilateral
+    }
+    /// Get the number of families with a superior ovary
+    pub fn get_superior(&self) -> usize {
+        self.superior
+    }
+    /// Get the number of families with a half-inferior ovary
+    pub fn get_half_inferior(&self) -> usize {
+        self.half_inferior
+    }
+    /// Get the number of families with an inferior ovary
+    pub fn get_inferior(&self) -> usize {
+        self.inferior
+    }
+    /// Get the number of families with connate petals
+    pub fn get_connate_petals(&self) -> usize {
+        self.connate_petals
+    }
+    /// Get the number of families with adnation
+    pub fn get_adnation(&self) -> usize {
+        self.adnation
+    }
+    /// Get the number of families with indefinitely many stamens
+    pub fn get_infinite_stamens(&self) -> usize {
+        self.infinite_stamens
+    }
+}
+
+// the features of a family, over all of its flower types
+#[derive(Default)]
+struct FamilyFeatures {
+    symmetry: BTreeSet<&'static str>,
+    merosity: BTreeSet<String>,
+    ovary: BTreeSet<Ovary>,
+    fruit: BTreeSet<String>,
+    connate_petals: bool,
+    adnation: bool,
+    infinite_stamens: bool,
+}
+
+impl FamilyFeatures {
+    fn add(&mut self, formula: &Formula) {
+        for symmetry in formula.get_symmetry() {
+            self.symmetry.insert(match symmetry {
+                Symmetry::Radial => "radial",
+                Symmetry::Bilateral(_) => "bilateral",
+                Symmetry::Asymmetry => "asymmetrical",
+                Symmetry::Spiral => "spiral",
+                Symmetry::Disymmetric => "disymmetric",
+            });
+        }
+        self.merosity.insert(formula.merosity().to_string());
+        self.ovary
+            .extend(formula.get_carpels().iter().flat_map(|e| e.get_ovary()));
+        // staminate flowers bear no fruit, which isn't a kind of fruit
+        for fruit in formula.get_fruit() {
+            if fruit.get_fruit() != Fruit::None {
+                self.fruit.insert(fruit.get_fruit().to_string());
+            }
+        }
+        self.connate_petals |= formula
+            .get_petals()
+            .as_ref()
+            .is_some_and(|e| e.get_connation());
+        self.adnation |= formula.has_adnation();
+        self.infinite_stamens |= formula
+            .get_stamens()
+            .as_ref()
+            .and_then(count_parts)
+            .is_some_and(|(_, max)| matches!(max, FloralPartNumber::Infinite));
+    }
+}
+
+// add one to the tally of each of the values
+fn tally<'a>(counts: &mut BTreeMap<String, usize>, values: impl IntoIterator<Item = &'a str>) {
+    for value in values {
+        *counts.entry(value.to_string()).or_default() += 1;
+    }
+}
+
+// the most common first, then alphabetically
+fn sort_tally(counts: BTreeMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts
+}
+
+impl Stats {
+    /// Compute the statistics of the formulae, given with the
+    /// order and family of each.
+    pub fn new<'a>(data: impl IntoIterator<Item = (&'a str, &'a str, &'a Formula)>) -> Self {
+        let mut families: BTreeMap<(&str, &str), FamilyFeatures> = BTreeMap::new();
+        let mut entries: BTreeMap<&str, usize> = BTreeMap::new();
+        for (order, family, formula) in data {
+            families.entry((order, family)).or_default().add(formula);
+            *entries.entry(order).or_default() += 1;
+        }
+
+        let mut stats = Stats {
+            orders: entries.len(),
+            families: families.len(),
+            entries: entries.values().sum(),
+            ..Default::default()
+        };
+        let (mut symmetry, mut merosity, mut ovary, mut fruit) = Default::default();
+        let mut per_order: BTreeMap<&str, OrderStats> = BTreeMap::new();
+
+        for ((order, _), features) in &families {
+            tally(&mut symmetry, features.symmetry.iter().copied());
+            tally(&mut merosity, features.merosity.iter().map(|e| e.as_str()));
+            tally(
+                &mut ovary,
+                features.ovary.iter().map(|e| match e {
+                    Ovary::Superior => "superior",
+                    Ovary::HalfInferior => "half-inferior",
+                    Ovary::Inferior => "inferior",
+                }),
+            );
+            tally(&mut fruit, features.fruit.iter().map(|e| e.as_str()));
+            stats.connate_petals += features.connate_petals as usize;
+            stats.adnation += features.adnation as usize;
+            stats.infinite_stamens += features.infinite_stamens as usize;
+
+            let order_stats = per_order.entry(order).or_insert_with(|| OrderStats {
+                order: order.to_string(),
+                entries: entries[order],
+                ..Default::default()
+            });
+            order_stats.families += 1;
+            order_stats.radial += features.symmetry.contains("radial") as usize;
+            order_stats.bilateral += features.symmetry.contains("bilateral") as usize;
+            order_stats.superior += features.ovary.contains(&Ovary::Superior) as usize;
+            order_stats.half_inferior += features.ovary.contains(&Ovary::HalfInferior) as usize;
+            order_stats.inferior += features.ovary.contains(&Ovary::Inferior) as usize;
+            order_stats.connate_petals += features.connate_petals as usize;
+            order_stats.adnation += features.adnation as usize;
+            order_stats.infinite_stamens += features.infinite_stamens as usize;
+        }
+
+        stats.symmetry = sort_tally(symmetry);
+        stats.merosity = sort_tally(merosity);
+        stats.ovary = sort_tally(ovary);
+        stats.fruit = sort_tally(fruit);
+        stats.per_order = per_order.into_values().collect();
+        stats
+    }
+
+    /// Get the number of orders
+    pub fn get_orders(&self) -> usize {
+        self.orders
+    }
+    /// Get the number of families
+    pub fn get_families(&self) -> usize {
+        self.families
+    }
+    /// Get the number of flower types
+    pub fn get_entries(&self) -> usize {
+        self.entries
+    }
+    /// Get the number of families with each kind of symmetry
+    pub fn get_symmetry(&self) -> &Vec<(String, usize)> {
+        &self.symmetry
+    }
+    /// Get the number of families with each merosity
+    pub fn get_merosity(&self) -> &Vec<(String, usize)> {
+        &self.merosity
+    }
+    /// Get the number of families with each ovary position
+    pub fn get_ovary(&self) -> &Vec<(String, usize)> {
+        &self.ovary
+    }
+    /// Get the number of families with each fruit type
+    pub fn get_fruit(&self) -> &Vec<(String, usize)> {
+        &self.fruit
+    }
+    /// Get the number of families with connate petals
+    pub fn get_connate_petals(&self) -> usize {
+        self.connate_petals
+    }
+    /// Get the number of families with adnation
+    pub fn get_adnation(&self) -> usize {
+        self.adnation
+    }
+    /// Get the number of families with indefinitely many stamens
+    pub fn get_infinite_stamens(&self) -> usize {
+        self.infinite_stamens
+    }
+    /// Get the statistics of each order
+    pub fn get_per_order(&self) -> &Vec<OrderStats> {
+        &self.per_order
+    }
+
+    /// The statistics as tables, each with a name.
+    pub fn tables(&self) -> Vec<(&'static str, Table)> {
+        let mut summary = Table::new(&["measure", "count"]);
+        for (measure, count) in [
+            ("orders", self.orders),
+            ("families", self.families),
+            ("flower types", self.entries),
+            ("families with connate petals", self.connate_petals),
+            ("families with adnation", self.adnation),
+            ("families with ∞ stamens", self.infinite_stamens),
+        ] {
+            summary.push_row(vec![measure.into(), count.into()]);
+        }
+
+        let distribution = |name: &str, counts: &Vec<(String, usize)>| {
+            let mut table = Table::new(&[name, "families"]);
+            for (value, count) in counts {
+                table.push_row(vec![value.as_str().into(), (*count).into()]);
+            }
+            table
+        };
+
+        let mut orders = Table::new(&[
+            "order",
+            "families",
+            "entries",
+            "radial",
+            "bilateral",
+            "superior",
+            "half_inferior",
+            "inferior",
+            "connate_petals",
+            "adnation",
+            "infinite_stamens",
+        ]);
+        for order in &self.per_order {
+            let counts = [
+                order.families,
+                order.entries,
+                order.radial,
+                order.bilateral,
+                order.superior,
+                order.half_inferior,
+                order.inferior,
+                order.connate_petals,
+                order.adnation,
+                order.infinite_stamens,
+            ];
+            let mut row: Vec<Value> = vec![order.order.as_str().into()];
+            row.extend(counts.into_iter().map(Value::from));
+            orders.push_row(row);
+        }
+
+        vec![
+            ("summary", summary),
+            ("symmetry", distribution("symmetry", &self.symmetry)),
+            ("merosity", distribution("merosity", &self.merosity)),
+            ("ovary", distribution("ovary", &self.ovary)),
+            ("fruit", distribution("fruit", &self.fruit)),
+            ("orders", orders),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::parse_formula;
+    use crate::output::{render_tables, OutputFormat};
+
+    #[test]
+    fn count_families_by_feature() {
+        let radial = parse_formula("*,K5,(C5),A∞,G_(2);capsule").unwrap();
+        let bilateral = parse_formula("X(down),K5,C5,A10,G^(2);berry").unwrap();
+        let staminate = parse_formula("*,K5,C5,A5,G0;no fruit").unwrap();
+        let stats = Stats::new([
+            ("Ericales", "a", &radial),
+            ("Ericales", "a", &staminate),
+            ("Ericales", "b", &bilateral),
+            ("Lamiales", "c", &bilateral),
+        ]);
+
+        assert_eq!(stats.get_orders(), 2);
+        assert_eq!(stats.get_families(), 3);
+        assert_eq!(stats.get_entries(), 4);
+        // families are counted once, however many flower types they have
+        assert_eq!(
+            stats.get_symmetry(),
+            &vec![("bilateral".to_string(), 2), ("radial".to_string(), 1)]
+        );
+        assert_eq!(
+            stats.get_fruit(),
+            &vec![("berry".to_string(), 2), ("capsule".to_string(), 1)]
+        );
+        assert_eq!(stats.get_connate_petals(), 1);
+        assert_eq!(stats.get_infinite_stamens(), 1);
+
+        let ericales = &stats.get_per_order()[0];
+        assert_eq!(ericales.get_order(), "Ericales");
+        assert_eq!(ericales.get_families(), 2);
+        assert_eq!(ericales.get_entries(), 3);
+        assert_eq!(ericales.get_superior(), 1);
+        assert_eq!(ericales.get_inferior(), 1);
+
+        let json = render_tables(&stats.tables(), OutputFormat::Json);
+        assert!(
+            json.starts_with("{\n  \"summary\": [\n    {\"measure\": \"orders\", \"count\": 2},")
+        );
+        assert!(json.contains("\"ovary\": [\n    {\"ovary\": \"inferior\", \"families\": 2},"));
+
+        // a family with superior and inferior ovaries is counted under both
+        let stats = Stats::new([("Ericales", "d", &radial), ("Ericales", "d", &bilateral)]);
+        assert_eq!(
+            stats.get_ovary(),
+            &vec![("inferior".to_string(), 1), ("superior".to_string(), 1)]
+        );
+        let ericales = &stats.get_per_order()[0];
+        assert_eq!((ericales.get_superior(), ericales.get_inferior()), (1, 1));
+
+        // half-inferior ovaries have a column of their own in each order
+        let half = parse_formula("*,K5,C5,A5,G~(2);capsule").unwrap();
+        let stats = Stats::new([("Saxifragales", "e", &half)]);
+        let saxifragales = &stats.get_per_order()[0];
+        assert_eq!(saxifragales.get_half_inferior(), 1);
+        assert_eq!(saxifragales.get_superior() + saxifragales.get_inferior(), 0);
+        let tables = stats.tables();
+        let (_, orders) = tables.iter().find(|(name, _)| *name == "orders").unwrap();
+        let tsv = orders.render(OutputFormat::Tsv);
+        assert!(tsv.contains("\tsuperior\thalf_inferior\tinferior\t"));
+    }
+}